@@ -0,0 +1,31 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use prijsplafond::model::{DemandModel, PriceLimitSimulator, SupplyModel};
+
+// A binding ceiling on the default straight curves, where every area has a
+// closed form.
+fn linear_market() -> PriceLimitSimulator {
+    PriceLimitSimulator { price_limit: 8.0, ..Default::default() }
+}
+
+// The same ceiling on curved supply and demand, where every area is
+// integrated over `integration_steps` slices.
+fn curved_market() -> PriceLimitSimulator {
+    PriceLimitSimulator {
+        price_limit: 8.0,
+        supply_model: SupplyModel::Quadratic { a: 0.05, b: 0.2, c: 5.0 },
+        demand_model: DemandModel::ConstantElasticity { a: 50.0, epsilon: 1.0 },
+        integration_steps: 200,
+        ..Default::default()
+    }
+}
+
+fn surplus(c: &mut Criterion) {
+    let linear = linear_market();
+    let curved = curved_market();
+    c.bench_function("calculate_surplus/linear", |b| b.iter(|| black_box(&linear).calculate_surplus()));
+    c.bench_function("calculate_surplus/integrated", |b| b.iter(|| black_box(&curved).calculate_surplus()));
+    c.bench_function("integrate_surplus/200_steps", |b| b.iter(|| black_box(&linear).integrate_surplus()));
+}
+
+criterion_group!(benches, surplus);
+criterion_main!(benches);
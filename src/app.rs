@@ -0,0 +1,4952 @@
+use eframe::egui;
+use egui_plot::{
+    Bar, BarChart, Corner, HLine, Legend, Line, LineStyle, MarkerShape, Plot, PlotBounds, PlotPoint, PlotPoints,
+    PlotUi, Points, Polygon, Text, VLine,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::ops::RangeInclusive;
+
+use crate::formula::Formula;
+use crate::model::{
+    AT_THRESHOLD, DEFAULT_SAMPLES, DemandModel, Levy, LineFit, MAX_QUANTITY, NumericWarning, PriceControl,
+    PriceLimitSimulator, QuotaStanding, round_to_cents, ShiftMode, SupplyModel, SurplusResult, trapezoid_area,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Language {
+    En,
+    Nl,
+}
+
+// What the market is about. The model is the same either way; a labour
+// market only renames its terms, so that a price floor reads as a minimum
+// wage.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+enum Domain {
+    #[default]
+    Goods,
+    Labor,
+}
+
+// The language every label and number is shown in, and the market whose
+// terms the labels use.
+#[derive(Clone, Copy)]
+struct Lang {
+    language: Language,
+    domain: Domain,
+}
+
+impl Lang {
+    // For files and LaTeX, which keep English decimal points.
+    const ENGLISH: Lang = Lang { language: Language::En, domain: Domain::Goods };
+
+    // Two decimals, with the decimal separator the language expects. Rounded
+    // to cents first, so money reads the same wherever it is shown.
+    fn fmt_num(self, value: f64) -> String {
+        self.localize_decimal(format!("{:.2}", round_to_cents(value)))
+    }
+
+    // As `fmt_num`, but always with a sign, for differences.
+    fn fmt_signed_num(self, value: f64) -> String {
+        self.localize_decimal(format!("{:+.2}", round_to_cents(value)))
+    }
+
+    // An amount of money or a price, led by the currency symbol.
+    fn fmt_money(self, currency: &str, value: f64) -> String {
+        let number = self.fmt_num(value);
+        match number.strip_prefix('-') {
+            Some(magnitude) => format!("-{}{}", currency, magnitude),
+            None => format!("{}{}", currency, number),
+        }
+    }
+
+    fn fmt_signed_money(self, currency: &str, value: f64) -> String {
+        let number = self.fmt_signed_num(value);
+        format!("{}{}{}", &number[..1], currency, &number[1..])
+    }
+
+    // A quantity followed by its unit, if there is one.
+    fn fmt_quantity(self, unit: &str, value: f64) -> String {
+        if unit.is_empty() {
+            self.fmt_num(value)
+        } else {
+            format!("{} {}", self.fmt_num(value), unit)
+        }
+    }
+
+    fn localize_decimal(self, number: String) -> String {
+        match self.language {
+            Language::En => number,
+            Language::Nl => number.replace('.', ","),
+        }
+    }
+
+    // Unknown keys are shown verbatim so a missing translation is easy to spot.
+    // In a labour market its own terms take the place of the general ones.
+    fn t(self, key: &'static str) -> &'static str {
+        let (table, labor) = match self.language {
+            Language::En => (EN, LABOR_EN),
+            Language::Nl => (NL, LABOR_NL),
+        };
+        let overrides = if self.domain == Domain::Labor { labor } else { &[] };
+        overrides.iter().chain(table).find(|(k, _)| *k == key).map_or(key, |(_, text)| text)
+    }
+}
+
+// The terms of a labour market, where firms buy work from workers: the
+// price is the wage, a floor is a minimum wage and the workers it leaves
+// without a job are the unemployed.
+const LABOR_EN: &[(&str, &str)] = &[
+    ("quantity", "Employment"),
+    ("price", "Wage"),
+    ("floor", "Minimum wage"),
+    ("price_floor", "Minimum wage"),
+    ("ceiling", "Wage cap"),
+    ("price_limit", "Wage limit"),
+    ("price_ceiling", "Wage limit"),
+    ("equilibrium_price", "Equilibrium Wage"),
+    ("buyer_price", "Wage paid"),
+    ("seller_price", "Wage received"),
+    ("supply_curve", "Labour Supply"),
+    ("demand_curve", "Labour Demand"),
+    ("consumer_surplus", "Firm Surplus"),
+    ("producer_surplus", "Worker Surplus"),
+    ("excess_supply", "Unemployment"),
+    ("excess_supply_bracket", "Unemployment"),
+    ("shortage", "Unfilled vacancies"),
+    ("quantity_supplied", "Labour supplied"),
+    ("quantity_demanded", "Labour demanded"),
+    ("demand_falls_from", "Labour demand falls from"),
+    ("supply_rises_from", "Labour supply rises from"),
+];
+
+const LABOR_NL: &[(&str, &str)] = &[
+    ("quantity", "Werkgelegenheid"),
+    ("price", "Loon"),
+    ("floor", "Minimumloon"),
+    ("price_floor", "Minimumloon"),
+    ("ceiling", "Loonplafond"),
+    ("price_limit", "Loongrens"),
+    ("price_ceiling", "Loongrens"),
+    ("equilibrium_price", "Evenwichtsloon"),
+    ("buyer_price", "Betaald loon"),
+    ("seller_price", "Ontvangen loon"),
+    ("supply_curve", "Arbeidsaanbod"),
+    ("demand_curve", "Arbeidsvraag"),
+    ("consumer_surplus", "Surplus werkgevers"),
+    ("producer_surplus", "Surplus werknemers"),
+    ("excess_supply", "Werkloosheid"),
+    ("excess_supply_bracket", "Werkloosheid"),
+    ("shortage", "Onvervulde vacatures"),
+    ("quantity_supplied", "Aangeboden arbeid"),
+    ("quantity_demanded", "Gevraagde arbeid"),
+    ("demand_falls_from", "De arbeidsvraag daalt van"),
+    ("supply_rises_from", "Het arbeidsaanbod stijgt van"),
+];
+
+const EN: &[(&str, &str)] = &[
+    ("title", "Price Limit Simulator"),
+    ("reset", "Reset"),
+    ("undo", "Undo"),
+    ("redo", "Redo"),
+    ("dark_mode", "Dark mode"),
+    ("preset", "Preset"),
+    ("preset_custom", "Custom"),
+    ("preset_binding_ceiling", "Binding ceiling"),
+    ("preset_non_binding_ceiling", "Non-binding ceiling"),
+    ("preset_supply_shock", "Supply shock"),
+    ("preset_price_floor", "Price floor"),
+    ("tutorial", "Lesson"),
+    ("back", "Back"),
+    ("next", "Next"),
+    ("end_tutorial", "End lesson"),
+    ("ratchet", "Ceiling ratchet"),
+    ("record", "Record"),
+    ("play_recording", "Play recording"),
+    ("stop", "Stop"),
+    ("changes_recorded", "changes recorded"),
+    ("shocks", "Shocks"),
+    ("shock_oil", "Oil price spike"),
+    ("shock_oil_caption", "Fuel and transport get dearer, raising the cost of every unit: supply shifts up by 4."),
+    ("shock_harvest", "Bumper harvest"),
+    ("shock_harvest_caption", "Good weather brings in more than usual at the same cost: supply shifts down by 3."),
+    ("shock_recession", "Recession"),
+    ("shock_recession_caption", "Incomes fall and buyers pay less for every unit: demand shifts down by 3."),
+    ("shock_craze", "Craze"),
+    (
+        "shock_craze_caption",
+        "The good becomes fashionable and buyers pay more for every unit: demand shifts up by 3.",
+    ),
+    ("pause", "Pause"),
+    ("play", "Play"),
+    ("close", "Close"),
+    (
+        "ratchet_announced",
+        "A ceiling is announced at the equilibrium price. Nothing changes yet: every buyer willing to pay \
+         it still finds a seller.",
+    ),
+    (
+        "ratchet_first_cut",
+        "The ceiling is cut below the equilibrium price. Sellers offer less, buyers want more, and a \
+         shortage opens up.",
+    ),
+    (
+        "ratchet_second_cut",
+        "A second cut of the same size deepens the shortage, and the deadweight loss grows by more than \
+         the first cut added.",
+    ),
+    (
+        "ratchet_third_cut",
+        "After a third cut so few units are offered that consumers as a whole are worse off than after \
+         the second: the lower price no longer makes up for what they cannot buy.",
+    ),
+    (
+        "tutorial_free_market",
+        "Without intervention the market clears where supply meets demand: every buyer willing to pay the \
+         equilibrium price gets the good, and the surplus areas are as large as they can be.",
+    ),
+    (
+        "tutorial_supply_shock",
+        "A supply shock raises the cost of producing every unit, shifting supply up. The equilibrium price \
+         rises, fewer units are traded, and both surpluses shrink.",
+    ),
+    (
+        "tutorial_ceiling",
+        "To keep the good affordable the government caps the price at the old equilibrium price. Sellers \
+         offer even less at that price while buyers want more: a shortage, and a deadweight loss on top \
+         of the shock.",
+    ),
+    ("preset_quota", "Quota"),
+    ("colorblind_mode", "Colorblind-safe colors"),
+    ("ceiling", "Ceiling"),
+    ("floor", "Floor"),
+    ("quota", "Quota"),
+    ("none", "None"),
+    ("clear", "Clear"),
+    ("clear_control", "Move the control to where it no longer binds, keeping the market"),
+    ("clear_levy", "Set the tax or subsidy to zero, keeping the market"),
+    ("price_limit", "Price Limit"),
+    ("price_ceiling", "Price Limit"),
+    ("price_floor", "Price Floor"),
+    ("binding_threshold", "Binding threshold"),
+    ("quota_not_binding", "Quota not binding: above what would trade without it"),
+    ("quota_at_threshold", "Quota not binding: exactly what would trade without it"),
+    ("binding", "Binding"),
+    ("not_binding", "Not binding"),
+    ("at_equilibrium", "At the equilibrium price: just binding"),
+    ("lift_ceiling", "Lift the ceiling gradually"),
+    ("best_ceiling", "Best ceiling for consumers"),
+    ("weighted_welfare", "Weighted welfare"),
+    ("consumer_weight", "Weight on consumers"),
+    ("best_ceiling_at_weight", "Best ceiling at this weight"),
+    ("no_ceiling_helps", "No binding ceiling raises weighted welfare"),
+    ("apply", "Set"),
+    ("waiting_cost_fraction", "Waiting Cost (share of consumer surplus)"),
+    ("tax", "Tax"),
+    ("subsidy", "Subsidy"),
+    ("tax_per_unit", "Tax per Unit"),
+    ("subsidy_per_unit", "Subsidy per Unit"),
+    ("supply_slope", "Supply Slope"),
+    ("supply_intercept", "Supply Intercept"),
+    ("supply_shift", "Supply Shift"),
+    ("quadratic", "Quadratic"),
+    ("supply_curvature", "Supply Curvature (a)"),
+    ("supply_linear_term", "Supply Slope at Zero (b)"),
+    ("supply_constant", "Supply Intercept (c)"),
+    ("demand_slope", "Demand Slope"),
+    ("demand_intercept", "Demand Intercept"),
+    ("linear", "Linear"),
+    ("constant_elasticity", "Constant elasticity"),
+    ("custom_formula", "Formula"),
+    ("two_groups", "Two groups"),
+    ("second_group_slope", "Second Group Slope"),
+    ("second_group_intercept", "Second Group Intercept"),
+    ("first_group_demand", "Demand of group 1"),
+    ("second_group_demand", "Demand of group 2"),
+    ("supply_formula", "Supply S(q) ="),
+    ("demand_formula", "Demand D(q) ="),
+    ("formula_error", "Cannot read the formula"),
+    ("demand_scale", "Demand Scale (a)"),
+    ("demand_elasticity", "Demand Elasticity (ε)"),
+    ("demand_shift", "Demand Shift"),
+    ("shift_history", "Recent shifts"),
+    ("shift_mode", "Shifts move the curves"),
+    ("parallel_shift", "in parallel"),
+    ("rotate_shift", "by rotating about the intercept"),
+    ("animate_shifts", "Animate shifts"),
+    ("arrow_keys_shift", "Up/Down arrows shift:"),
+    ("no_equilibrium", "No market equilibrium for these parameters"),
+    ("near_vertical", "A curve is too steep where they cross to show the areas"),
+    ("consumer_surplus", "Consumer Surplus"),
+    ("producer_surplus", "Producer Surplus"),
+    ("deadweight_loss", "Deadweight Loss"),
+    ("total_surplus", "Total Surplus"),
+    ("demand_price_elasticity", "Price Elasticity of Demand"),
+    ("supply_price_elasticity", "Price Elasticity of Supply"),
+    ("price_change", "%Δ equilibrium price from shift"),
+    ("quantity_change", "%Δ quantity"),
+    ("regulated", "Regulated"),
+    ("free_market", "Free market"),
+    ("transfer_to_consumers", "Moved from producers to consumers"),
+    ("lost_entirely", "lost entirely"),
+    ("efficiency_loss", "Efficiency loss"),
+    ("waiting_cost", "Lost to waiting (in deadweight loss)"),
+    ("tax_revenue", "Tax Revenue"),
+    ("subsidy_cost", "Subsidy Cost"),
+    ("buyer_price", "Buyer Price"),
+    ("seller_price", "Seller Price"),
+    ("expenditure_and_revenue", "Expenditure = revenue"),
+    ("expenditure", "Buyer expenditure"),
+    ("revenue", "Seller revenue"),
+    ("borne_by_buyers", "Tax borne by buyers"),
+    ("borne_by_sellers", "by sellers"),
+    ("shortage", "Shortage"),
+    ("excess_supply", "Excess Supply"),
+    ("excess_supply_bracket", "Surplus (excess supply)"),
+    ("overproduction", "Overproduction"),
+    ("quantity", "Quantity"),
+    ("price", "Price"),
+    ("supply_curve", "Supply Curve"),
+    ("equilibrium", "Equilibrium"),
+    ("traded_point", "Traded Point"),
+    ("market_clears", "✔ The market clears"),
+    ("choke_price", "Choke price"),
+    ("max_quantity", "Max quantity"),
+    ("demand_curve", "Demand Curve"),
+    ("supply_slope_here", "Marginal cost slope (dS/dq)"),
+    ("demand_slope_here", "Marginal willingness to pay slope (dD/dq)"),
+    ("welfare_sweep", "Welfare vs. Price Limit"),
+    ("money_amount", "Amount"),
+    ("tax_sweep", "Revenue vs. Tax"),
+    ("surplus_breakdown", "Surplus breakdown"),
+    ("surplus_as_share", "Surplus as % of free market"),
+    ("substitute_market", "Substitute Market"),
+    ("cross_price", "Cross-price Response"),
+    ("normalize_prices", "Compare with the main market, prices relative to each equilibrium"),
+    ("price_relative", "Price / equilibrium price"),
+    ("main_supply", "Supply (main market)"),
+    ("main_demand", "Demand (main market)"),
+    ("spillover", "Demand shift from the main market"),
+    ("demand_without_spillover", "Demand without spillover"),
+    ("peak_revenue", "Revenue-maximizing tax"),
+    ("x_max", "Max Quantity"),
+    ("y_max", "Max Price"),
+    ("area_opacity", "Area Opacity"),
+    ("domain", "Market"),
+    ("goods_market", "Goods"),
+    ("labor_market", "Labour"),
+    ("currency", "Currency"),
+    ("unit", "Unit"),
+    ("grid", "Grid"),
+    ("monte_carlo", "Uncertain shifts"),
+    ("shift_std_dev", "Std. dev. of the shifts"),
+    ("draws", "Draws"),
+    ("equilibrium_price", "Equilibrium Price"),
+    ("as_set", "As set"),
+    ("equations", "Equations"),
+    ("ceiling_binds_sellers", "The ceiling binds, so sellers receive"),
+    ("floor_binds_buyers", "The floor binds, so buyers pay"),
+    ("quota_binds", "The quota binds, so buyers bid the price up to what the last unit is worth to them"),
+    ("solve_for_quantity", "and the traded quantity solves"),
+    ("dimensions", "Triangle dimensions"),
+    ("guides", "Equilibrium guides"),
+    ("compare_interventions", "Compare interventions"),
+    ("price_quantities", "Quantities at the price"),
+    ("quantity_supplied", "Quantity supplied"),
+    ("quantity_demanded", "Quantity demanded"),
+    ("at_price", "At a price of"),
+    ("warning_not_finite", "Some figures are infinite or undefined; do not trust them."),
+    (
+        "warning_quantity_capped",
+        "A quantity reached the end of the range the model considers and was cut off there.",
+    ),
+    (
+        "warning_willingness_capped",
+        "Willingness to pay is capped near zero quantity, and the consumer surplus with it.",
+    ),
+    ("warning_negative_limit", "A price limit or quota below zero counts as zero."),
+    ("surplus_residual", "The surplus areas miss the area between the curves by"),
+    ("highest_total_surplus", "Highest total surplus"),
+    ("no_intervention", "No intervention"),
+    ("severity", "Severity (share of equilibrium)"),
+    ("log_scale", "log scale"),
+    ("trapezoids", "Integration trapezoids"),
+    ("trapezoid_steps", "Trapezoids"),
+    ("quantity_on_y", "Quantity on the vertical axis"),
+    ("compact", "Compact"),
+    ("outcome", "Outcome"),
+    ("plot_summary", "Plot of supply and demand"),
+    ("graph_description", "Description of the graph"),
+    ("demand_falls_from", "Demand falls from"),
+    ("supply_rises_from", "Supply rises from"),
+    ("to", "to"),
+    ("at", "at"),
+    ("legend", "Legend"),
+    ("top_left", "top left"),
+    ("top_right", "top right"),
+    ("bottom_left", "bottom left"),
+    ("bottom_right", "bottom right"),
+    ("fit_view", "Fit view"),
+    ("reset_zoom", "Reset zoom"),
+    ("no_trade", "No trade occurs"),
+    ("set_ceiling_here", "Set ceiling here"),
+    ("add_marker", "Add marker"),
+    ("clear_markers", "Clear markers"),
+    ("marker", "Marker"),
+    ("load_data", "Load data"),
+    ("observations", "Observations"),
+    ("standard_error", "standard error"),
+    ("confidence_band", "Fit ± one standard error"),
+    ("snapshot", "Snapshot"),
+    ("clear_snapshot", "Clear snapshot"),
+    ("changes_since_snapshot", "Changes since the snapshot"),
+    ("no_changes", "No parameters changed"),
+    ("snapshot_supply", "Supply (snapshot)"),
+    ("snapshot_demand", "Demand (snapshot)"),
+    ("gallery", "Gallery"),
+    ("surplus_records", "Lowest and highest total surplus"),
+    ("lowest", "Lowest"),
+    ("highest", "Highest"),
+    ("reached_here", "reached by the current market"),
+    ("reset_records", "Reset"),
+    ("add_to_gallery", "Add current market"),
+    ("scenario", "Scenario"),
+    ("open", "Open"),
+    ("remove", "Remove"),
+    ("curve_values", "Curve Values"),
+    ("demand_minus_supply", "Demand − Supply"),
+    ("export_csv", "Export CSV"),
+    ("export_sweep", "Export welfare sweep"),
+    ("export_png", "Export PNG"),
+    ("export_latex", "Export LaTeX"),
+    ("copy_latex", "Copy LaTeX"),
+    ("copied_latex", "The worked calculation is on the clipboard, as LaTeX"),
+    ("copy_summary", "Copy summary"),
+    ("copied_summary", "A one-line summary is on the clipboard"),
+    ("exported_to", "Exported to"),
+    ("export_failed", "Export failed"),
+    ("save_scenario", "Save Scenario"),
+    ("load_scenario", "Load Scenario"),
+    ("save_recording", "Save Recording"),
+    ("unsaved_changes", "Unsaved changes"),
+    ("save_before_closing", "The market has changed since it was last saved. Save the scenario to"),
+    ("save", "Save"),
+    ("discard", "Discard"),
+    ("cancel", "Cancel"),
+    ("lock", "Lock against changes"),
+    ("load_recording", "Load Recording"),
+    ("saved_to", "Saved to"),
+    ("save_failed", "Save failed"),
+    ("loaded", "Loaded"),
+    ("load_failed", "Load failed"),
+    ("settings", "Settings"),
+    ("repaint", "Repaint"),
+    ("reactive", "On input only"),
+    ("continuous", "Continuously"),
+    ("max_fps", "Frame rate cap (fps)"),
+    ("curve_samples", "Curve samples"),
+    ("line_width", "Line width"),
+    ("dashed", "Dashed"),
+    ("snap_to", "Snap to multiples of"),
+    ("help_title", "About this model"),
+    (
+        "help_supply",
+        "The supply curve S(q) is the lowest price at which sellers are willing to offer the q-th unit:",
+    ),
+    (
+        "help_demand",
+        "The demand curve D(q) is the most buyers are willing to pay for the q-th unit:",
+    ),
+    (
+        "help_controls",
+        "Without intervention the market settles where the curves cross, at quantity q* and price P*. \
+         A price ceiling below P* caps the price, so only as much is traded as sellers offer at the ceiling. \
+         A price floor above P* keeps the price up, so only as much is traded as buyers want at the floor. \
+         A quota caps the quantity directly. A tax or subsidy per unit drives a wedge between the price \
+         buyers pay and the price sellers receive. Dragging a straight demand curve away from its ends \
+         turns it about the equilibrium, changing its elasticity but not q* or P*.",
+    ),
+    (
+        "help_surplus",
+        "Surplus is measured over the units actually traded, from q = 0 up to the traded quantity:",
+    ),
+    (
+        "help_linear",
+        "For straight curves these integrals are triangles and trapezoids and are computed exactly; \
+         otherwise they are evaluated numerically.",
+    ),
+];
+
+const NL: &[(&str, &str)] = &[
+    ("title", "Prijsplafond-simulator"),
+    ("reset", "Herstellen"),
+    ("undo", "Ongedaan maken"),
+    ("redo", "Opnieuw"),
+    ("dark_mode", "Donkere modus"),
+    ("preset", "Voorinstelling"),
+    ("preset_custom", "Eigen"),
+    ("preset_binding_ceiling", "Bindend plafond"),
+    ("preset_non_binding_ceiling", "Niet-bindend plafond"),
+    ("preset_supply_shock", "Aanbodschok"),
+    ("preset_price_floor", "Prijsbodem"),
+    ("preset_quota", "Quotum"),
+    ("tutorial", "Les"),
+    ("back", "Terug"),
+    ("next", "Volgende"),
+    ("end_tutorial", "Les beëindigen"),
+    ("ratchet", "Stapsgewijs plafond"),
+    ("record", "Opnemen"),
+    ("play_recording", "Opname afspelen"),
+    ("stop", "Stoppen"),
+    ("changes_recorded", "wijzigingen opgenomen"),
+    ("shocks", "Schokken"),
+    ("shock_oil", "Olieprijsschok"),
+    (
+        "shock_oil_caption",
+        "Brandstof en vervoer worden duurder, en daarmee elke eenheid: het aanbod verschuift 4 omhoog.",
+    ),
+    ("shock_harvest", "Recordoogst"),
+    (
+        "shock_harvest_caption",
+        "Goed weer levert meer op dan gewoonlijk tegen dezelfde kosten: het aanbod verschuift 3 omlaag.",
+    ),
+    ("shock_recession", "Recessie"),
+    (
+        "shock_recession_caption",
+        "Inkomens dalen en kopers betalen minder voor elke eenheid: de vraag verschuift 3 omlaag.",
+    ),
+    ("shock_craze", "Rage"),
+    (
+        "shock_craze_caption",
+        "Het goed raakt in de mode en kopers betalen meer voor elke eenheid: de vraag verschuift 3 omhoog.",
+    ),
+    ("pause", "Pauze"),
+    ("play", "Afspelen"),
+    ("close", "Sluiten"),
+    (
+        "ratchet_announced",
+        "Er wordt een plafond op de evenwichtsprijs aangekondigd. Er verandert nog niets: iedere koper die \
+         die prijs wil betalen vindt nog een verkoper.",
+    ),
+    (
+        "ratchet_first_cut",
+        "Het plafond wordt onder de evenwichtsprijs verlaagd. Verkopers bieden minder aan, kopers willen \
+         meer, en er ontstaat een tekort.",
+    ),
+    (
+        "ratchet_second_cut",
+        "Een even grote tweede verlaging vergroot het tekort, en het welvaartsverlies groeit meer dan bij \
+         de eerste verlaging.",
+    ),
+    (
+        "ratchet_third_cut",
+        "Na een derde verlaging wordt zo weinig aangeboden dat consumenten samen slechter af zijn dan na \
+         de tweede: de lagere prijs weegt niet meer op tegen wat ze niet kunnen kopen.",
+    ),
+    (
+        "tutorial_free_market",
+        "Zonder ingrijpen komt de markt uit waar aanbod en vraag elkaar snijden: iedere koper die de \
+         evenwichtsprijs wil betalen krijgt het goed, en de surplusvlakken zijn zo groot als mogelijk.",
+    ),
+    (
+        "tutorial_supply_shock",
+        "Een aanbodschok maakt het produceren van elke eenheid duurder, waardoor het aanbod omhoog \
+         verschuift. De evenwichtsprijs stijgt, er wordt minder verhandeld en beide surplussen krimpen.",
+    ),
+    (
+        "tutorial_ceiling",
+        "Om het goed betaalbaar te houden stelt de overheid een prijsplafond in op de oude evenwichtsprijs. \
+         Verkopers bieden bij die prijs nog minder aan terwijl kopers meer willen: een tekort, en bovenop \
+         de schok een welvaartsverlies.",
+    ),
+    ("colorblind_mode", "Kleurenblindveilige kleuren"),
+    ("ceiling", "Plafond"),
+    ("floor", "Bodem"),
+    ("quota", "Quotum"),
+    ("none", "Geen"),
+    ("clear", "Wissen"),
+    ("clear_control", "Zet de maatregel waar hij niet meer bindt, met behoud van de markt"),
+    ("clear_levy", "Zet de belasting of subsidie op nul, met behoud van de markt"),
+    ("price_limit", "Prijsgrens"),
+    ("price_ceiling", "Prijsplafond"),
+    ("price_floor", "Prijsbodem"),
+    ("binding_threshold", "Bindingsdrempel"),
+    ("quota_not_binding", "Quotum niet bindend: boven wat er zonder verhandeld zou worden"),
+    ("quota_at_threshold", "Quotum niet bindend: precies wat er zonder verhandeld zou worden"),
+    ("binding", "Bindend"),
+    ("not_binding", "Niet bindend"),
+    ("at_equilibrium", "Op de evenwichtsprijs: net bindend"),
+    ("lift_ceiling", "Plafond geleidelijk opheffen"),
+    ("best_ceiling", "Beste plafond voor consumenten"),
+    ("weighted_welfare", "Gewogen welvaart"),
+    ("consumer_weight", "Gewicht consumenten"),
+    ("best_ceiling_at_weight", "Beste plafond bij dit gewicht"),
+    ("no_ceiling_helps", "Geen bindend plafond verhoogt de gewogen welvaart"),
+    ("apply", "Instellen"),
+    ("waiting_cost_fraction", "Wachtkosten (aandeel consumentensurplus)"),
+    ("tax", "Belasting"),
+    ("subsidy", "Subsidie"),
+    ("tax_per_unit", "Belasting per eenheid"),
+    ("subsidy_per_unit", "Subsidie per eenheid"),
+    ("supply_slope", "Helling aanbod"),
+    ("supply_intercept", "Snijpunt aanbod"),
+    ("supply_shift", "Verschuiving aanbod"),
+    ("quadratic", "Kwadratisch"),
+    ("supply_curvature", "Kromming aanbod (a)"),
+    ("supply_linear_term", "Helling aanbod bij nul (b)"),
+    ("supply_constant", "Snijpunt aanbod (c)"),
+    ("demand_slope", "Helling vraag"),
+    ("demand_intercept", "Snijpunt vraag"),
+    ("linear", "Lineair"),
+    ("constant_elasticity", "Constante elasticiteit"),
+    ("custom_formula", "Formule"),
+    ("two_groups", "Twee groepen"),
+    ("second_group_slope", "Helling tweede groep"),
+    ("second_group_intercept", "Snijpunt tweede groep"),
+    ("first_group_demand", "Vraag van groep 1"),
+    ("second_group_demand", "Vraag van groep 2"),
+    ("supply_formula", "Aanbod S(q) ="),
+    ("demand_formula", "Vraag D(q) ="),
+    ("formula_error", "Formule niet leesbaar"),
+    ("demand_scale", "Schaal vraag (a)"),
+    ("demand_elasticity", "Elasticiteit vraag (ε)"),
+    ("demand_shift", "Verschuiving vraag"),
+    ("shift_history", "Recente verschuivingen"),
+    ("shift_mode", "Verschuivingen bewegen de curves"),
+    ("parallel_shift", "evenwijdig"),
+    ("rotate_shift", "door te draaien om het snijpunt met de prijsas"),
+    ("animate_shifts", "Verschuivingen animeren"),
+    ("arrow_keys_shift", "Pijltjes omhoog/omlaag verschuiven:"),
+    ("no_equilibrium", "Geen marktevenwicht bij deze parameters"),
+    ("near_vertical", "Een curve is te steil bij het snijpunt om de oppervlakken te tonen"),
+    ("consumer_surplus", "Consumentensurplus"),
+    ("producer_surplus", "Producentensurplus"),
+    ("deadweight_loss", "Welvaartsverlies"),
+    ("total_surplus", "Totaal surplus"),
+    ("demand_price_elasticity", "Prijselasticiteit vraag"),
+    ("supply_price_elasticity", "Prijselasticiteit aanbod"),
+    ("price_change", "%Δ evenwichtsprijs door verschuiving"),
+    ("quantity_change", "%Δ hoeveelheid"),
+    ("regulated", "Gereguleerd"),
+    ("free_market", "Vrije markt"),
+    ("transfer_to_consumers", "Van producenten naar consumenten verschoven"),
+    ("lost_entirely", "volledig verloren"),
+    ("efficiency_loss", "Efficiëntieverlies"),
+    ("waiting_cost", "Verloren aan wachten (in welvaartsverlies)"),
+    ("tax_revenue", "Belastingopbrengst"),
+    ("subsidy_cost", "Subsidiekosten"),
+    ("buyer_price", "Kopersprijs"),
+    ("seller_price", "Verkopersprijs"),
+    ("expenditure_and_revenue", "Uitgaven = omzet"),
+    ("expenditure", "Uitgaven kopers"),
+    ("revenue", "Omzet verkopers"),
+    ("borne_by_buyers", "Belasting gedragen door kopers"),
+    ("borne_by_sellers", "door verkopers"),
+    ("shortage", "Tekort"),
+    ("excess_supply", "Overaanbod"),
+    ("excess_supply_bracket", "Overschot (overaanbod)"),
+    ("overproduction", "Overproductie"),
+    ("quantity", "Hoeveelheid"),
+    ("price", "Prijs"),
+    ("supply_curve", "Aanbodcurve"),
+    ("equilibrium", "Evenwicht"),
+    ("traded_point", "Verhandeld punt"),
+    ("market_clears", "✔ De markt ruimt"),
+    ("choke_price", "Verstikkingsprijs"),
+    ("max_quantity", "Max. hoeveelheid"),
+    ("demand_curve", "Vraagcurve"),
+    ("supply_slope_here", "Helling marginale kosten (dS/dq)"),
+    ("demand_slope_here", "Helling marginale betalingsbereidheid (dD/dq)"),
+    ("welfare_sweep", "Welvaart per prijsgrens"),
+    ("money_amount", "Bedrag"),
+    ("tax_sweep", "Opbrengst per belasting"),
+    ("surplus_breakdown", "Verdeling van het surplus"),
+    ("surplus_as_share", "Surplus als % van vrije markt"),
+    ("substitute_market", "Substituutmarkt"),
+    ("cross_price", "Kruiselingse prijsreactie"),
+    ("normalize_prices", "Vergelijken met de hoofdmarkt, prijzen ten opzichte van elk evenwicht"),
+    ("price_relative", "Prijs / evenwichtsprijs"),
+    ("main_supply", "Aanbod (hoofdmarkt)"),
+    ("main_demand", "Vraag (hoofdmarkt)"),
+    ("spillover", "Vraagverschuiving door de hoofdmarkt"),
+    ("demand_without_spillover", "Vraag zonder overloop"),
+    ("peak_revenue", "Opbrengstmaximaliserende belasting"),
+    ("x_max", "Max. hoeveelheid"),
+    ("y_max", "Max. prijs"),
+    ("area_opacity", "Dekking vlakken"),
+    ("domain", "Markt"),
+    ("goods_market", "Goederen"),
+    ("labor_market", "Arbeid"),
+    ("currency", "Valuta"),
+    ("unit", "Eenheid"),
+    ("grid", "Raster"),
+    ("monte_carlo", "Onzekere verschuivingen"),
+    ("shift_std_dev", "Standaardafwijking van de verschuivingen"),
+    ("draws", "Trekkingen"),
+    ("equilibrium_price", "Evenwichtsprijs"),
+    ("as_set", "Zoals ingesteld"),
+    ("equations", "Vergelijkingen"),
+    ("ceiling_binds_sellers", "Het plafond is bindend, dus verkopers ontvangen"),
+    ("floor_binds_buyers", "De bodem is bindend, dus kopers betalen"),
+    (
+        "quota_binds",
+        "Het quotum is bindend, dus kopers bieden de prijs op tot wat de laatste eenheid hun waard is",
+    ),
+    ("solve_for_quantity", "en de verhandelde hoeveelheid volgt uit"),
+    ("dimensions", "Afmetingen driehoeken"),
+    ("guides", "Hulplijnen evenwicht"),
+    ("compare_interventions", "Ingrepen vergelijken"),
+    ("price_quantities", "Hoeveelheden bij de prijs"),
+    ("quantity_supplied", "Aangeboden hoeveelheid"),
+    ("quantity_demanded", "Gevraagde hoeveelheid"),
+    ("at_price", "Bij een prijs van"),
+    ("warning_not_finite", "Sommige uitkomsten zijn oneindig of onbepaald; vertrouw ze niet."),
+    (
+        "warning_quantity_capped",
+        "Een hoeveelheid bereikte de rand van het bereik van het model en is daar afgekapt.",
+    ),
+    (
+        "warning_willingness_capped",
+        "De betalingsbereidheid is bij kleine hoeveelheden begrensd, en het consumentensurplus dus ook.",
+    ),
+    ("warning_negative_limit", "Een prijsgrens of quotum onder nul telt als nul."),
+    ("surplus_residual", "De surplusvlakken wijken af van het vlak tussen de curven met"),
+    ("highest_total_surplus", "Hoogste totale surplus"),
+    ("no_intervention", "Geen ingreep"),
+    ("severity", "Zwaarte (deel van evenwicht)"),
+    ("log_scale", "logaritmische schaal"),
+    ("trapezoids", "Integratietrapezia"),
+    ("trapezoid_steps", "Trapezia"),
+    ("quantity_on_y", "Hoeveelheid op de verticale as"),
+    ("compact", "Compact"),
+    ("outcome", "Uitkomst"),
+    ("plot_summary", "Grafiek van aanbod en vraag"),
+    ("graph_description", "Beschrijving van de grafiek"),
+    ("demand_falls_from", "De vraag daalt van"),
+    ("supply_rises_from", "Het aanbod stijgt van"),
+    ("to", "tot"),
+    ("at", "bij"),
+    ("legend", "Legenda"),
+    ("top_left", "linksboven"),
+    ("top_right", "rechtsboven"),
+    ("bottom_left", "linksonder"),
+    ("bottom_right", "rechtsonder"),
+    ("fit_view", "Passend maken"),
+    ("reset_zoom", "Zoom herstellen"),
+    ("no_trade", "Er wordt niets verhandeld"),
+    ("set_ceiling_here", "Plafond hier leggen"),
+    ("add_marker", "Markering toevoegen"),
+    ("clear_markers", "Markeringen wissen"),
+    ("marker", "Markering"),
+    ("load_data", "Gegevens laden"),
+    ("observations", "Waarnemingen"),
+    ("standard_error", "standaardfout"),
+    ("confidence_band", "Schatting ± één standaardfout"),
+    ("snapshot", "Momentopname"),
+    ("clear_snapshot", "Momentopname wissen"),
+    ("changes_since_snapshot", "Wijzigingen sinds de momentopname"),
+    ("no_changes", "Geen parameters gewijzigd"),
+    ("snapshot_supply", "Aanbod (momentopname)"),
+    ("snapshot_demand", "Vraag (momentopname)"),
+    ("gallery", "Galerij"),
+    ("surplus_records", "Laagste en hoogste totaal surplus"),
+    ("lowest", "Laagste"),
+    ("highest", "Hoogste"),
+    ("reached_here", "bereikt met de huidige markt"),
+    ("reset_records", "Opnieuw"),
+    ("add_to_gallery", "Huidige markt toevoegen"),
+    ("scenario", "Scenario"),
+    ("open", "Openen"),
+    ("remove", "Verwijderen"),
+    ("curve_values", "Waarden van de curven"),
+    ("demand_minus_supply", "Vraag − aanbod"),
+    ("export_csv", "CSV exporteren"),
+    ("export_sweep", "Welvaart per prijsgrens exporteren"),
+    ("export_png", "PNG exporteren"),
+    ("export_latex", "LaTeX exporteren"),
+    ("copy_latex", "LaTeX kopiëren"),
+    ("copied_latex", "De uitgewerkte berekening staat als LaTeX op het klembord"),
+    ("copy_summary", "Samenvatting kopiëren"),
+    ("copied_summary", "Een samenvatting van één regel staat op het klembord"),
+    ("exported_to", "Geëxporteerd naar"),
+    ("export_failed", "Exporteren mislukt"),
+    ("save_scenario", "Scenario opslaan"),
+    ("load_scenario", "Scenario laden"),
+    ("save_recording", "Opname opslaan"),
+    ("unsaved_changes", "Niet-opgeslagen wijzigingen"),
+    ("save_before_closing", "De markt is veranderd sinds die is opgeslagen. Het scenario opslaan in"),
+    ("save", "Opslaan"),
+    ("discard", "Niet opslaan"),
+    ("cancel", "Annuleren"),
+    ("lock", "Vergrendelen tegen wijzigingen"),
+    ("load_recording", "Opname laden"),
+    ("saved_to", "Opgeslagen in"),
+    ("save_failed", "Opslaan mislukt"),
+    ("loaded", "Geladen"),
+    ("load_failed", "Laden mislukt"),
+    ("settings", "Instellingen"),
+    ("repaint", "Hertekenen"),
+    ("reactive", "Alleen bij invoer"),
+    ("continuous", "Doorlopend"),
+    ("max_fps", "Maximale beeldsnelheid (fps)"),
+    ("curve_samples", "Meetpunten per curve"),
+    ("line_width", "Lijndikte"),
+    ("dashed", "Gestreept"),
+    ("snap_to", "Afronden op veelvouden van"),
+    ("help_title", "Over dit model"),
+    (
+        "help_supply",
+        "De aanbodcurve S(q) is de laagste prijs waarvoor verkopers de q-de eenheid willen aanbieden:",
+    ),
+    (
+        "help_demand",
+        "De vraagcurve D(q) is het meeste dat kopers voor de q-de eenheid willen betalen:",
+    ),
+    (
+        "help_controls",
+        "Zonder ingrijpen komt de markt uit waar de curven elkaar snijden, bij hoeveelheid q* en prijs P*. \
+         Een prijsplafond onder P* begrenst de prijs, zodat er niet meer verhandeld wordt dan verkopers \
+         bij het plafond aanbieden. Een minimumprijs boven P* houdt de prijs hoog, zodat er niet meer \
+         verhandeld wordt dan kopers bij die prijs willen. Een quotum begrenst de hoeveelheid direct. \
+         Een belasting of subsidie per eenheid drijft een wig tussen de prijs die kopers betalen en de \
+         prijs die verkopers ontvangen. Een rechte vraagcurve ergens langs de lijn verslepen draait \
+         hem om het evenwicht, zodat de elasticiteit verandert maar q* en P* niet.",
+    ),
+    (
+        "help_surplus",
+        "Surplus wordt gemeten over de eenheden die echt verhandeld worden, van q = 0 tot de verhandelde \
+         hoeveelheid:",
+    ),
+    (
+        "help_linear",
+        "Bij rechte curven zijn deze integralen driehoeken en trapezia en worden ze exact berekend; \
+         anders worden ze numeriek benaderd.",
+    ),
+];
+
+// Fill colors for the shaded plot regions.
+struct Palette {
+    consumer_surplus: egui::Color32,
+    producer_surplus: egui::Color32,
+    deadweight_loss: egui::Color32,
+    tax_revenue: egui::Color32,
+    subsidy_cost: egui::Color32,
+    demand_style: LineStyle,
+    price_limit_style: LineStyle,
+}
+
+impl Palette {
+    fn new(dark_mode: bool, colorblind_mode: bool) -> Self {
+        if colorblind_mode {
+            return Self::colorblind(dark_mode);
+        }
+        // The light fills wash out against a dark background, so dark mode
+        // uses deeper, more saturated tones.
+        if dark_mode {
+            Self {
+                consumer_surplus: egui::Color32::from_rgb(40, 90, 170),
+                producer_surplus: egui::Color32::from_rgb(170, 50, 50),
+                deadweight_loss: egui::Color32::from_rgba_unmultiplied(230, 190, 0, 120),
+                tax_revenue: egui::Color32::from_rgb(40, 130, 60),
+                subsidy_cost: egui::Color32::from_rgb(110, 70, 150),
+                demand_style: LineStyle::Solid,
+                price_limit_style: LineStyle::Solid,
+            }
+        } else {
+            Self {
+                consumer_surplus: egui::Color32::LIGHT_BLUE,
+                producer_surplus: egui::Color32::LIGHT_RED,
+                deadweight_loss: egui::Color32::from_rgba_unmultiplied(255, 220, 0, 100),
+                tax_revenue: egui::Color32::LIGHT_GREEN,
+                subsidy_cost: egui::Color32::from_rgb(200, 170, 230),
+                demand_style: LineStyle::Solid,
+                price_limit_style: LineStyle::Solid,
+            }
+        }
+    }
+
+    // Blue against orange from the Okabe-Ito set, which stays apart under
+    // every common colour vision deficiency, with the curves also told apart
+    // by dash pattern.
+    fn colorblind(dark_mode: bool) -> Self {
+        let (consumer_surplus, producer_surplus) = if dark_mode {
+            (egui::Color32::from_rgb(0, 114, 178), egui::Color32::from_rgb(213, 94, 0))
+        } else {
+            (egui::Color32::from_rgb(86, 180, 233), egui::Color32::from_rgb(230, 159, 0))
+        };
+        Self {
+            consumer_surplus,
+            producer_surplus,
+            deadweight_loss: egui::Color32::from_rgba_unmultiplied(240, 228, 66, 130),
+            tax_revenue: egui::Color32::from_rgb(0, 158, 115),
+            subsidy_cost: egui::Color32::from_rgb(204, 121, 167),
+            demand_style: LineStyle::dashed_loose(),
+            price_limit_style: LineStyle::dotted_dense(),
+        }
+    }
+}
+
+// How thick a line on the main plot is drawn and whether it is dashed. Off,
+// it keeps the palette's own style.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct LineLook {
+    width: f32,
+    dashed: bool,
+}
+
+impl LineLook {
+    // Thicker than egui's default, to stay legible on a projector.
+    const DEFAULT: LineLook = LineLook { width: 3.0, dashed: false };
+
+    fn style(self, otherwise: LineStyle) -> LineStyle {
+        if self.dashed {
+            LineStyle::dashed_loose()
+        } else {
+            otherwise
+        }
+    }
+
+    fn edit(&mut self, ui: &mut egui::Ui, lang: Lang, label: egui::Id) {
+        ui.add(egui::DragValue::new(&mut self.width).range(0.5..=8.0).speed(0.1)).labelled_by(label);
+        ui.checkbox(&mut self.dashed, lang.t("dashed"));
+    }
+}
+
+// Which outcome of the random draws the histogram counts.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum DrawnMeasure {
+    EquilibriumPrice,
+    ConsumerSurplus,
+}
+
+impl DrawnMeasure {
+    fn key(self) -> &'static str {
+        match self {
+            DrawnMeasure::EquilibriumPrice => "equilibrium_price",
+            DrawnMeasure::ConsumerSurplus => "consumer_surplus",
+        }
+    }
+
+    fn of(self, result: &SurplusResult) -> f64 {
+        match self {
+            DrawnMeasure::EquilibriumPrice => result.equilibrium_price,
+            DrawnMeasure::ConsumerSurplus => result.consumer_surplus,
+        }
+    }
+}
+
+// Which curve a data file is fitted to, or which shift the arrow keys move.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Curve {
+    Supply,
+    Demand,
+}
+
+// A real-world event told as a move of the curves, with the translation keys
+// of its name and of the story behind it.
+struct Shock {
+    name: &'static str,
+    caption: &'static str,
+    supply_shift: f64,
+    demand_shift: f64,
+}
+
+const SHOCKS: [Shock; 4] = [
+    Shock { name: "shock_oil", caption: "shock_oil_caption", supply_shift: 4.0, demand_shift: 0.0 },
+    Shock { name: "shock_harvest", caption: "shock_harvest_caption", supply_shift: -3.0, demand_shift: 0.0 },
+    Shock { name: "shock_recession", caption: "shock_recession_caption", supply_shift: 0.0, demand_shift: -3.0 },
+    Shock { name: "shock_craze", caption: "shock_craze_caption", supply_shift: 0.0, demand_shift: 3.0 },
+];
+
+// Built-in demonstrations, keyed by the translation of their name.
+fn presets() -> [(&'static str, PriceLimitSimulator); 5] {
+    [
+        ("preset_binding_ceiling", PriceLimitSimulator { price_limit: 8.0, ..Default::default() }),
+        ("preset_non_binding_ceiling", PriceLimitSimulator { price_limit: 12.0, ..Default::default() }),
+        ("preset_supply_shock", PriceLimitSimulator { supply_shift: 4.0, ..Default::default() }),
+        (
+            "preset_price_floor",
+            PriceLimitSimulator {
+                price_control: PriceControl::Floor,
+                price_limit: 12.0,
+                ..Default::default()
+            },
+        ),
+        (
+            "preset_quota",
+            PriceLimitSimulator {
+                price_control: PriceControl::Quota,
+                quota: 6.0,
+                ..Default::default()
+            },
+        ),
+    ]
+}
+
+// The guided lesson on a supply shock, one market per step, keyed by the
+// translation of its caption. It ends on the supply shock preset.
+fn tutorial_steps() -> [(&'static str, PriceLimitSimulator); 3] {
+    let (_, shock_with_ceiling) = presets()
+        .into_iter()
+        .find(|(name, _)| *name == "preset_supply_shock")
+        .expect("the supply shock preset exists");
+    [
+        (
+            "tutorial_free_market",
+            PriceLimitSimulator { price_control: PriceControl::None, ..Default::default() },
+        ),
+        (
+            "tutorial_supply_shock",
+            PriceLimitSimulator { price_control: PriceControl::None, ..shock_with_ceiling.clone() },
+        ),
+        ("tutorial_ceiling", shock_with_ceiling),
+    ]
+}
+
+// Restored on the next launch; the skipped fields only matter while running.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulatorApp {
+    model: PriceLimitSimulator,
+    language: Language,
+    domain: Domain,
+    dark_mode: bool,
+    colorblind_mode: bool,
+    export_path: String,
+    sweep_path: String,
+    png_path: String,
+    latex_path: String,
+    scenario_path: String,
+    recording_path: String,
+    // Where the main plot was drawn last frame, to crop screenshots to.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    plot_rect: Option<egui::Rect>,
+    #[serde(skip)]
+    status: String,
+    #[serde(skip)]
+    reset_plot: bool,
+    // The market as last saved to or loaded from a scenario file, or as the
+    // window opened on, and whether closing waits on asking to save a
+    // different one.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    saved_model: PriceLimitSimulator,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    confirm_close: bool,
+    #[serde(skip)]
+    fit_view: bool,
+    // Whether the main plot has been panned, zoomed or fitted since it was
+    // last reset. Until it has, the view widens to keep the equilibrium and
+    // the control's line in sight, however far a shift moves them.
+    #[serde(skip)]
+    user_zoomed: bool,
+    // What the pointer was over last frame, which decides if a drag moves it
+    // or pans the plot, and what is being dragged.
+    #[serde(skip)]
+    hovered_handle: Option<DragHandle>,
+    #[serde(skip)]
+    dragged_handle: Option<DragHandle>,
+    show_supply: bool,
+    show_demand: bool,
+    show_price_limit: bool,
+    show_consumer_surplus: bool,
+    show_producer_surplus: bool,
+    show_deadweight_loss: bool,
+    show_welfare_sweep: bool,
+    show_tax_sweep: bool,
+    // The outcome's surplus and loss as one stacked bar.
+    show_breakdown: bool,
+    // Give the surplus and loss as shares of the free market's total surplus
+    // rather than in money, in the outcome and the stacked bar.
+    surplus_as_share: bool,
+    // Treat the shifts as uncertain, normal around the set ones, and plot how
+    // the chosen outcome spreads over many draws.
+    show_monte_carlo: bool,
+    shift_std_dev: f64,
+    monte_carlo_draws: usize,
+    monte_carlo_measure: DrawnMeasure,
+    // A second market for a substitute good, whose demand follows what
+    // happens in the main one.
+    show_substitute: bool,
+    substitute: PriceLimitSimulator,
+    cross_price: f64,
+    // Overlay the main market on the substitute's plot, each market's prices
+    // divided by its own free-market equilibrium price.
+    normalize_prices: bool,
+    show_grid: bool,
+    // The curves' equations and the equilibrium they solve to, as text.
+    show_equations: bool,
+    // Bracket the base and heights of the surplus areas on the main plot.
+    show_dimensions: bool,
+    // Dotted lines from the equilibrium to both axes, labelled there.
+    show_guides: bool,
+    // Both quantities at the price limit, binding or not, and at the hovered
+    // price in the plot's tooltip.
+    show_price_quantities: bool,
+    // Mark where a ceiling, a floor, a tax and a quota, each set the same
+    // share away from the equilibrium, would leave the market.
+    show_interventions: bool,
+    intervention_severity: f64,
+    // Welfare counting consumer surplus at this weight and producer surplus
+    // at the rest, with the ceiling that maximizes it.
+    show_weighted_welfare: bool,
+    welfare_weight: f64,
+    // Outline the trapezoids the numeric integration adds up, this many
+    // under each surplus area.
+    show_trapezoids: bool,
+    trapezoid_steps: usize,
+    // Draw the main plot with log-scaled axes instead.
+    log_axes: bool,
+    // Draw it with price across and quantity up, as some textbooks do.
+    transposed: bool,
+    // The plots' legends, which can be moved or hidden when they cover the
+    // curves.
+    show_legend: bool,
+    legend_corner: Corner,
+    // Controls in a side panel next to the plot rather than above it, as
+    // they also are in any window too narrow for both.
+    compact: bool,
+    #[serde(skip)]
+    show_help: bool,
+    // The step of the guided lesson being shown, counting from one, or zero
+    // outside the lesson.
+    #[serde(skip)]
+    tutorial_step: usize,
+    #[serde(skip)]
+    show_settings: bool,
+    // Repaint every frame rather than only on input, and how often at most
+    // while doing so or while animating.
+    continuous_repaint: bool,
+    max_fps: f32,
+    // Pieces the curves are tabulated and exported in, and the segments a
+    // curved one is drawn from before refining.
+    curve_samples: usize,
+    supply_look: LineLook,
+    demand_look: LineLook,
+    limit_look: LineLook,
+    // Round the price limit to a multiple of `price_step` whenever the
+    // slider, a click or a drag moves it.
+    snap_price_limit: bool,
+    price_step: f64,
+    // A frozen copy of an earlier market, drawn faded for comparison.
+    snapshot: Option<PriceLimitSimulator>,
+    // Named markets built up during a lecture, to overlay and switch between.
+    gallery: Vec<GalleryEntry>,
+    #[serde(skip)]
+    gallery_name: String,
+    data_path: String,
+    fitted_curve: Curve,
+    // Up and Down nudge this curve's shift, Left and Right the price limit.
+    nudged_shift: Curve,
+    // Quantity and price pairs from the last data file, with the line fitted
+    // to them.
+    #[serde(skip)]
+    observations: Vec<[f64; 2]>,
+    // Points dropped on the main plot from its context menu, and where the
+    // menu last opened.
+    #[serde(skip)]
+    markers: Vec<[f64; 2]>,
+    #[serde(skip)]
+    context_point: Option<PlotPoint>,
+    #[serde(skip)]
+    observation_fit: Option<LineFit>,
+    // The plotted region always reaches at least this far.
+    x_max: f64,
+    y_max: f64,
+    // How much of the consumer and producer surplus fills shows, so the
+    // curves underneath stay visible.
+    area_opacity: f32,
+    // Shown with every amount of money and every quantity respectively.
+    currency: String,
+    unit: String,
+    #[serde(skip)]
+    welfare_sweep: Option<WelfareSweep>,
+    #[serde(skip)]
+    tax_sweep: Option<TaxSweep>,
+    #[serde(skip)]
+    monte_carlo: Option<MonteCarlo>,
+    #[serde(skip)]
+    sampled_curves: Option<SampledCurves>,
+    #[serde(skip)]
+    supply_input: FormulaInput,
+    #[serde(skip)]
+    demand_input: FormulaInput,
+    // Whether the caches above may be brought up to date this frame, and
+    // when they last were during a drag.
+    #[serde(skip)]
+    recompute_due: bool,
+    #[serde(skip)]
+    last_recompute: f64,
+    // With animation on the shift sliders set these, and the model's shifts
+    // ease toward them over about half a second.
+    animate_shifts: bool,
+    #[serde(skip)]
+    supply_shift_target: f64,
+    #[serde(skip)]
+    demand_shift_target: f64,
+    #[serde(skip)]
+    ceiling_lift: Option<CeilingLift>,
+    // Whether a control bound last frame, and how long ago the market last
+    // went from rationed to clearing, while that is still being flashed.
+    #[serde(skip)]
+    was_binding: bool,
+    // The lowest and highest total surplus any valid market reached since
+    // the start or the last reset.
+    #[serde(skip)]
+    lowest_surplus: Option<SurplusRecord>,
+    #[serde(skip)]
+    highest_surplus: Option<SurplusRecord>,
+    #[serde(skip)]
+    clears_flash: Option<f64>,
+    #[serde(skip)]
+    best_ceiling: Option<BestCeiling>,
+    #[serde(skip)]
+    ratchet: Option<Ratchet>,
+    // Each change to the market while recording, with the seconds since the
+    // recording began, and when it began while it is still going.
+    #[serde(skip)]
+    recording: Vec<(f64, PriceLimitSimulator)>,
+    #[serde(skip)]
+    recording_since: Option<f64>,
+    #[serde(skip)]
+    replay: Option<Replay>,
+    // The last shock from the menu, whose story stays on screen.
+    #[serde(skip)]
+    applied_shock: Option<&'static Shock>,
+    // Earlier markets to step back to, and the one last recorded, which is
+    // what undo returns to from a change still in progress.
+    #[serde(skip)]
+    undo_stack: Vec<PriceLimitSimulator>,
+    #[serde(skip)]
+    redo_stack: Vec<PriceLimitSimulator>,
+    #[serde(skip)]
+    recorded: PriceLimitSimulator,
+    // The supply and demand shifts after each of the last changes, oldest
+    // first, for the sparkline under the shift sliders.
+    #[serde(skip)]
+    shift_history: VecDeque<[f64; 2]>,
+    #[serde(skip)]
+    locks: Locks,
+    #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+    #[serde(skip)]
+    timings: FrameTimings,
+}
+
+// Where the time of a frame went, shown over the window in debug builds to
+// catch a feature slowing it down. The web has no clock `Instant` can read.
+#[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+#[derive(Default)]
+struct FrameTimings {
+    surplus: std::time::Duration,
+    sampling: std::time::Duration,
+}
+
+// The ceiling that leaves buyers the most surplus, shown until anything other
+// than the limit itself changes.
+struct BestCeiling {
+    market: PriceLimitSimulator,
+    price_limit: f64,
+    consumer_surplus: f64,
+}
+
+// A binding ceiling being raised to above the equilibrium price, so the
+// shortage can be watched closing.
+struct CeilingLift {
+    from: f64,
+    to: f64,
+    elapsed: f64,
+    // The ceiling as last set, to notice when the user takes it over.
+    current: f64,
+}
+
+impl CeilingLift {
+    const SECONDS: f64 = 3.0;
+
+    // The ceiling after another `dt` seconds, easing in and out, or `None`
+    // once it has arrived.
+    fn advance(&mut self, dt: f64) -> Option<f64> {
+        self.elapsed += dt;
+        let t = (self.elapsed / Self::SECONDS).min(1.0);
+        self.current = self.from + (self.to - self.from) * t * t * (3.0 - 2.0 * t);
+        (t < 1.0).then_some(self.current)
+    }
+}
+
+// A ceiling lowered step by step, each step captioned, moving on after a few
+// seconds while playing or whenever Next is pressed.
+struct Ratchet {
+    step: usize,
+    elapsed: f64,
+    playing: bool,
+}
+
+impl Ratchet {
+    const SECONDS: f64 = 4.0;
+    // The ceilings in turn, for the default market, with the translation key
+    // of their captions.
+    const STEPS: [(f64, &'static str); 4] = [
+        (10.0, "ratchet_announced"),
+        (9.0, "ratchet_first_cut"),
+        (8.0, "ratchet_second_cut"),
+        (7.0, "ratchet_third_cut"),
+    ];
+}
+
+// A recording being played back, `elapsed` seconds in with the markets
+// before `next` already shown.
+struct Replay {
+    elapsed: f64,
+    next: usize,
+}
+
+// Sliders pinned for the session, by the translation key of their label, so
+// that adjusting the others cannot move them by accident.
+#[derive(Default)]
+struct Locks(HashSet<&'static str>);
+
+impl Locks {
+    fn holds(&self, key: &str) -> bool {
+        self.0.contains(key)
+    }
+
+    // A `slider_with_input` labelled `key`, behind a padlock that disables it
+    // while closed.
+    fn slider(
+        &mut self,
+        ui: &mut egui::Ui,
+        value: &mut f64,
+        range: RangeInclusive<f64>,
+        key: &'static str,
+        lang: Lang,
+    ) -> egui::Response {
+        ui.horizontal(|ui| {
+            let locked = self.holds(key);
+            let padlock = ui.selectable_label(locked, if locked { "🔒" } else { "🔓" });
+            if describe(padlock, lang.t("lock"), locked).clicked() && !self.0.remove(key) {
+                self.0.insert(key);
+            }
+            ui.add_enabled_ui(!locked, |ui| slider_with_input(ui, value, range, lang.t(key))).inner
+        })
+        .inner
+    }
+
+    // Whether dragging `handle` on the plot would move a locked value.
+    fn hold_handle(&self, handle: DragHandle) -> bool {
+        match handle {
+            DragHandle::PriceLimit => self.holds("price_limit"),
+            DragHandle::SupplyIntercept => self.holds("supply_intercept") || self.holds("supply_constant"),
+            DragHandle::DemandIntercept => self.holds("demand_intercept"),
+            DragHandle::DemandPivot => self.holds("demand_slope") || self.holds("demand_intercept"),
+        }
+    }
+}
+
+// A formula as typed, with why it does not parse if it does not. Only text
+// that parses reaches the market, which keeps the last one that did.
+#[derive(Default)]
+struct FormulaInput {
+    text: String,
+    error: Option<String>,
+}
+
+// A market kept in the gallery under a name, drawn over the plot while shown.
+#[derive(Serialize, Deserialize)]
+struct GalleryEntry {
+    name: String,
+    market: PriceLimitSimulator,
+    shown: bool,
+}
+
+// The gallery's overlays, each kept in its own colour.
+const GALLERY_COLORS: [egui::Color32; 4] = [
+    egui::Color32::from_rgb(0, 130, 160),
+    egui::Color32::from_rgb(170, 90, 0),
+    egui::Color32::from_rgb(120, 60, 160),
+    egui::Color32::from_rgb(90, 120, 30),
+];
+
+// A total surplus reached this session and the market that reached it.
+struct SurplusRecord {
+    total_surplus: f64,
+    market: PriceLimitSimulator,
+}
+
+// How long the check mark shows once a binding control stops binding.
+const CLEARS_FLASH_SECONDS: f64 = 1.5;
+
+// How far past the equilibrium and the control's line a view that follows
+// them reaches, as a factor of their position.
+const VIEW_MARGIN: f64 = 1.1;
+
+// How many steps back undo can go.
+const UNDO_DEPTH: usize = 50;
+
+// How many changes of the shifts the sparkline looks back over.
+const SHIFT_HISTORY_LEN: usize = 40;
+
+// How often the sweeps, draws and sampled curves catch up while something is
+// being dragged.
+const DRAG_RECOMPUTE_HZ: f64 = 30.0;
+
+// Welfare at every price limit, kept until anything other than the limit
+// itself changes.
+struct WelfareSweep {
+    market: PriceLimitSimulator,
+    consumer_surplus: Vec<[f64; 2]>,
+    producer_surplus: Vec<[f64; 2]>,
+    deadweight_loss: Vec<[f64; 2]>,
+}
+
+// Tax revenue and deadweight loss at every tax per unit, with the tax that
+// raises the most, kept until anything other than the tax changes.
+struct TaxSweep {
+    market: PriceLimitSimulator,
+    tax_revenue: Vec<[f64; 2]>,
+    deadweight_loss: Vec<[f64; 2]>,
+    peak_revenue: [f64; 2],
+}
+
+// The outcomes of the random draws, kept until the market or the number and
+// spread of the draws change.
+struct MonteCarlo {
+    market: PriceLimitSimulator,
+    std_dev: f64,
+    draws: usize,
+    outcomes: Vec<SurplusResult>,
+}
+
+// The drawn supply and demand points, kept until the curves, the plotted
+// range or the sampling change.
+struct SampledCurves {
+    curves: PriceLimitSimulator,
+    x_max: f64,
+    samples: usize,
+    supply: Vec<[f64; 2]>,
+    demand: Vec<[f64; 2]>,
+    // Each group's own demand, when demand adds up two of them.
+    demand_groups: Option<[Vec<[f64; 2]>; 2]>,
+}
+
+// What happened inside the plot this frame.
+struct PlotInteraction {
+    clicked: Option<PlotPoint>,
+    right_clicked: Option<PlotPoint>,
+    pointer: Option<PlotPoint>,
+    near_handle: Option<DragHandle>,
+    bounds: PlotBounds,
+}
+
+// Parts of the main plot that can be dragged up and down.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DragHandle {
+    PriceLimit,
+    // Where the supply or demand curve meets the price axis.
+    SupplyIntercept,
+    DemandIntercept,
+    // Anywhere else along straight demand, which turns about the
+    // equilibrium so only its elasticity changes.
+    DemandPivot,
+}
+
+impl Default for SimulatorApp {
+    fn default() -> Self {
+        Self {
+            model: PriceLimitSimulator::default(),
+            language: Language::En,
+            domain: Domain::Goods,
+            dark_mode: false,
+            colorblind_mode: false,
+            export_path: "prijsplafond.csv".to_owned(),
+            sweep_path: "welfare_sweep.csv".to_owned(),
+            png_path: "prijsplafond.png".to_owned(),
+            latex_path: "prijsplafond.tex".to_owned(),
+            scenario_path: "scenario.json".to_owned(),
+            recording_path: "recording.json".to_owned(),
+            #[cfg(not(target_arch = "wasm32"))]
+            plot_rect: None,
+            status: String::new(),
+            reset_plot: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            saved_model: PriceLimitSimulator::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            confirm_close: false,
+            fit_view: false,
+            user_zoomed: false,
+            hovered_handle: None,
+            dragged_handle: None,
+            show_supply: true,
+            show_demand: true,
+            show_price_limit: true,
+            show_consumer_surplus: true,
+            show_producer_surplus: true,
+            show_deadweight_loss: true,
+            show_welfare_sweep: false,
+            show_tax_sweep: false,
+            show_breakdown: false,
+            surplus_as_share: false,
+            show_monte_carlo: false,
+            shift_std_dev: 1.0,
+            monte_carlo_draws: 500,
+            monte_carlo_measure: DrawnMeasure::EquilibriumPrice,
+            show_substitute: false,
+            substitute: PriceLimitSimulator { price_control: PriceControl::None, ..Default::default() },
+            cross_price: 0.5,
+            normalize_prices: false,
+            show_grid: true,
+            show_equations: false,
+            show_dimensions: false,
+            show_guides: false,
+            show_price_quantities: false,
+            show_interventions: false,
+            intervention_severity: 0.2,
+            show_weighted_welfare: false,
+            welfare_weight: 0.5,
+            show_trapezoids: false,
+            trapezoid_steps: 8,
+            log_axes: false,
+            transposed: false,
+            show_legend: true,
+            legend_corner: Corner::RightTop,
+            compact: false,
+            show_help: false,
+            tutorial_step: 0,
+            show_settings: false,
+            continuous_repaint: false,
+            max_fps: 60.0,
+            curve_samples: DEFAULT_SAMPLES,
+            supply_look: LineLook::DEFAULT,
+            demand_look: LineLook::DEFAULT,
+            limit_look: LineLook::DEFAULT,
+            snap_price_limit: false,
+            price_step: 1.0,
+            snapshot: None,
+            gallery: Vec::new(),
+            gallery_name: String::new(),
+            data_path: "data.csv".to_owned(),
+            fitted_curve: Curve::Demand,
+            nudged_shift: Curve::Supply,
+            observations: Vec::new(),
+            markers: Vec::new(),
+            context_point: None,
+            observation_fit: None,
+            welfare_sweep: None,
+            tax_sweep: None,
+            monte_carlo: None,
+            sampled_curves: None,
+            supply_input: FormulaInput::default(),
+            demand_input: FormulaInput::default(),
+            recompute_due: true,
+            last_recompute: 0.0,
+            x_max: 20.0,
+            y_max: 20.0,
+            area_opacity: 0.4,
+            currency: "€".to_owned(),
+            unit: String::new(),
+            animate_shifts: false,
+            supply_shift_target: 0.0,
+            demand_shift_target: 0.0,
+            ceiling_lift: None,
+            was_binding: false,
+            lowest_surplus: None,
+            highest_surplus: None,
+            clears_flash: None,
+            best_ceiling: None,
+            ratchet: None,
+            recording: Vec::new(),
+            recording_since: None,
+            replay: None,
+            applied_shock: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            recorded: PriceLimitSimulator::default(),
+            shift_history: VecDeque::new(),
+            locks: Locks::default(),
+            #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+            timings: FrameTimings::default(),
+        }
+    }
+}
+
+impl SimulatorApp {
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut app: Self = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+            .unwrap_or_default();
+        app.settle_shifts();
+        app.recorded = app.model.clone();
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            app.saved_model = app.model.clone();
+        }
+        app
+    }
+
+    // As `new`, but opening on `model` rather than the market left last time.
+    pub fn with_scenario(cc: &eframe::CreationContext<'_>, model: PriceLimitSimulator) -> Self {
+        let mut app = Self::new(cc);
+        app.model = model;
+        app.settle_shifts();
+        app.recorded = app.model.clone();
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            app.saved_model = app.model.clone();
+        }
+        app
+    }
+
+    fn lang(&self) -> Lang {
+        Lang { language: self.language, domain: self.domain }
+    }
+
+    // Ends any shift animation at the model's current values, for when they
+    // are replaced wholesale.
+    fn settle_shifts(&mut self) {
+        self.supply_shift_target = self.model.supply_shift;
+        self.demand_shift_target = self.model.demand_shift;
+    }
+
+    // Writes the part of a window screenshot showing the main plot, or the
+    // whole window if the plot has not been drawn yet, with the market in
+    // words as its description for readers that cannot see it.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_png(
+        &self,
+        screenshot: &egui::ColorImage,
+        pixels_per_point: f32,
+        path: &str,
+    ) -> Result<(), png::EncodingError> {
+        let plot = match self.plot_rect {
+            Some(rect) => screenshot.region(&rect, Some(pixels_per_point)),
+            None => screenshot.clone(),
+        };
+        let [width, height] = plot.size;
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let mut encoder = png::Encoder::new(file, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        // International text, as the description can be Dutch and hold any
+        // currency symbol.
+        encoder.add_itxt_chunk("Description".to_owned(), self.graph_description())?;
+        encoder.write_header()?.write_image_data(plot.as_raw())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_csv(&self, path: &str) -> std::io::Result<()> {
+        let result = self.model.calculate_surplus();
+        let binding =
+            result.binding && matches!(self.model.price_control, PriceControl::Ceiling | PriceControl::Floor);
+
+        let mut csv = String::from("quantity,supply_price,demand_price,price_limit\n");
+        for q in self.model.sampled_quantities(self.x_max, self.curve_samples) {
+            let price_limit = if binding { self.model.price_limit.to_string() } else { String::new() };
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                q,
+                self.model.supply_formula(q),
+                self.model.demand_formula(q),
+                price_limit
+            ));
+        }
+        csv.push_str(&format!("# consumer_surplus,{}\n", result.consumer_surplus));
+        csv.push_str(&format!("# producer_surplus,{}\n", result.producer_surplus));
+        csv.push_str(&format!("# deadweight_loss,{}\n", result.deadweight_loss));
+        csv.push_str(&format!("# quantity,{}\n", result.quantity));
+        csv.push_str(&format!("# price,{}\n", result.price));
+
+        std::fs::write(path, csv)
+    }
+
+    // The welfare sweep, one row per price limit, after comment lines giving
+    // the market it was run on.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_sweep_csv(&self, path: &str) -> std::io::Result<()> {
+        // English keeps the decimal points the rows use.
+        let (supply_equation, demand_equation) = curve_equations(&self.model, Lang::ENGLISH);
+        let mut csv = format!("# supply,{supply_equation}\n# demand,{demand_equation}\n");
+        csv.push_str(&format!("# price_control,{:?}\n", self.model.price_control));
+        csv.push_str(&format!("# tax_per_unit,{}\n", self.model.tax()));
+        csv.push_str(&format!("# subsidy_per_unit,{}\n", self.model.subsidy()));
+        csv.push_str("price_limit,consumer_surplus,producer_surplus,deadweight_loss,total_surplus\n");
+        for (price_limit, result) in self.model.price_limit_sweep() {
+            csv.push_str(&format!(
+                "{},{},{},{},{}\n",
+                price_limit,
+                result.consumer_surplus,
+                result.producer_surplus,
+                result.deadweight_loss,
+                result.total_surplus()
+            ));
+        }
+        std::fs::write(path, csv)
+    }
+
+    fn welfare_sweep(&mut self) -> &WelfareSweep {
+        let market = PriceLimitSimulator { price_limit: 0.0, ..self.model.clone() };
+        if self.welfare_sweep.as_ref().is_none_or(|sweep| self.recompute_due && sweep.market != market) {
+            let sweep = market.price_limit_sweep();
+            let series = |measure: fn(&SurplusResult) -> f64| {
+                sweep.iter().map(|(price_limit, result)| [*price_limit, measure(result)]).collect()
+            };
+            self.welfare_sweep = Some(WelfareSweep {
+                consumer_surplus: series(|result| result.consumer_surplus),
+                producer_surplus: series(|result| result.producer_surplus),
+                deadweight_loss: series(|result| result.deadweight_loss),
+                market,
+            });
+        }
+        self.welfare_sweep.as_ref().unwrap()
+    }
+
+    fn tax_sweep(&mut self) -> &TaxSweep {
+        let market = PriceLimitSimulator { tax_per_unit: 0.0, ..self.model.clone() };
+        if self.tax_sweep.as_ref().is_none_or(|sweep| self.recompute_due && sweep.market != market) {
+            let sweep = market.tax_sweep();
+            let tax_revenue: Vec<[f64; 2]> =
+                sweep.iter().map(|(tax, result)| [*tax, result.tax_revenue]).collect();
+            let peak_revenue = tax_revenue.iter().copied().fold([0.0, 0.0], |peak, point| {
+                if point[1] > peak[1] { point } else { peak }
+            });
+            self.tax_sweep = Some(TaxSweep {
+                deadweight_loss: sweep.iter().map(|(tax, result)| [*tax, result.deadweight_loss]).collect(),
+                tax_revenue,
+                peak_revenue,
+                market,
+            });
+        }
+        self.tax_sweep.as_ref().unwrap()
+    }
+
+    fn monte_carlo(&mut self) -> &MonteCarlo {
+        // Fixed, so the histogram holds still until something changes.
+        const SEED: u64 = 1;
+        let (std_dev, draws) = (self.shift_std_dev, self.monte_carlo_draws);
+        let stale = self.monte_carlo.as_ref().is_none_or(|monte_carlo| {
+            let changed =
+                monte_carlo.market != self.model || monte_carlo.std_dev != std_dev || monte_carlo.draws != draws;
+            self.recompute_due && changed
+        });
+        if stale {
+            self.monte_carlo = Some(MonteCarlo {
+                outcomes: self.model.monte_carlo(std_dev, draws, SEED),
+                market: self.model.clone(),
+                std_dev,
+                draws,
+            });
+        }
+        self.monte_carlo.as_ref().unwrap()
+    }
+
+    // How far along the quantity axis the main plot reaches: the set maximum,
+    // or past the equilibrium with some margin while the view follows it.
+    fn view_x_max(&self) -> f64 {
+        match self.model.find_equilibrium() {
+            Some((quantity, _)) if !self.user_zoomed => self.x_max.max(VIEW_MARGIN * quantity).min(MAX_QUANTITY),
+            _ => self.x_max,
+        }
+    }
+
+    fn sampled_curves(&mut self) -> &SampledCurves {
+        // Only the curves themselves and their shifts decide where they run.
+        let curves = PriceLimitSimulator {
+            price_limit: 0.0,
+            price_control: PriceControl::None,
+            quota: 0.0,
+            tax_per_unit: 0.0,
+            subsidy_per_unit: 0.0,
+            integration_steps: 0,
+            ..self.model.clone()
+        };
+        let (x_max, samples) = (self.view_x_max(), self.curve_samples);
+        let stale = self.sampled_curves.as_ref().is_none_or(|cached| {
+            self.recompute_due && (cached.curves != curves || cached.x_max != x_max || cached.samples != samples)
+        });
+        if stale {
+            #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+            let started = std::time::Instant::now();
+            self.sampled_curves = Some(SampledCurves {
+                supply: curves.supply_points(x_max, samples),
+                demand: curves.demand_points(x_max, samples),
+                demand_groups: curves.demand_group_points(x_max),
+                curves,
+                x_max,
+                samples,
+            });
+            #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+            {
+                self.timings.sampling += started.elapsed();
+            }
+        }
+        self.sampled_curves.as_ref().unwrap()
+    }
+
+    // The market as the controls have set it, with any shift still easing
+    // counted as already there.
+    fn settled_model(&self) -> PriceLimitSimulator {
+        let mut model = self.model.clone();
+        if self.animate_shifts {
+            model.supply_shift = self.supply_shift_target;
+            model.demand_shift = self.demand_shift_target;
+        }
+        if let Some(lift) = &self.ceiling_lift {
+            model.price_limit = lift.to;
+        }
+        model
+    }
+
+    // Remembers the previous market once a change is finished, that is once
+    // no slider is held and no value is being typed.
+    fn record_history(&mut self, ctx: &egui::Context) {
+        if ctx.input(|input| input.pointer.any_down()) || ctx.wants_keyboard_input() {
+            return;
+        }
+        let current = self.settled_model();
+        let shifts = [current.supply_shift, current.demand_shift];
+        if self.shift_history.back() != Some(&shifts) {
+            self.shift_history.push_back(shifts);
+            if self.shift_history.len() > SHIFT_HISTORY_LEN {
+                self.shift_history.pop_front();
+            }
+        }
+        if current != self.recorded {
+            current.log_state();
+            self.undo_stack.push(std::mem::replace(&mut self.recorded, current));
+            if self.undo_stack.len() > UNDO_DEPTH {
+                self.undo_stack.remove(0);
+            }
+            self.redo_stack.clear();
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some(previous) = self.undo_stack.pop() {
+            self.redo_stack.push(self.settled_model());
+            self.restore(previous);
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(next) = self.redo_stack.pop() {
+            self.undo_stack.push(self.settled_model());
+            self.restore(next);
+        }
+    }
+
+    fn restore(&mut self, model: PriceLimitSimulator) {
+        self.recorded = model.clone();
+        self.model = model;
+        self.settle_shifts();
+    }
+
+    // The last few supply and demand shifts side by side, one step per change.
+    fn show_shift_history(&self, ui: &mut egui::Ui) {
+        let lang = self.lang();
+        let series = |pick: usize| -> PlotPoints {
+            self.shift_history.iter().enumerate().map(|(i, shifts)| [i as f64, shifts[pick]]).collect()
+        };
+        ui.label(lang.t("shift_history"));
+        Plot::new("shift_history")
+            .height(60.0)
+            .show_axes([false, true])
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .allow_boxed_zoom(false)
+            .include_y(0.0)
+            .legend(Legend::default().position(Corner::LeftTop))
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(series(0)).name(lang.t("supply_shift")).width(1.0));
+                plot_ui.line(Line::new(series(1)).name(lang.t("demand_shift")).width(1.0));
+            });
+    }
+
+    // While a slider or handle is dragged the caches catch up at most
+    // `DRAG_RECOMPUTE_HZ` times a second, and once more on release.
+    fn throttle_recompute(&mut self, ctx: &egui::Context) {
+        let now = ctx.input(|input| input.time);
+        if ctx.dragged_id().is_none() {
+            self.recompute_due = true;
+            return;
+        }
+        let wait = 1.0 / DRAG_RECOMPUTE_HZ - (now - self.last_recompute);
+        self.recompute_due = wait <= 0.0;
+        if self.recompute_due {
+            self.last_recompute = now;
+        } else {
+            ctx.request_repaint_after(std::time::Duration::from_secs_f64(wait));
+        }
+    }
+
+    fn frame_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs_f32(1.0 / self.max_fps)
+    }
+
+    // The control, the shifts, both points and the welfare figures on one
+    // line, in the chosen language, currency and unit, for pasting into chat.
+    fn summary_line(&self, result: &SurplusResult) -> String {
+        let lang = self.lang();
+        let (currency, unit) = (self.currency.as_str(), self.unit.as_str());
+        let price_limit = lang.fmt_money(currency, self.model.price_limit);
+        let control = match self.model.price_control {
+            PriceControl::Ceiling => format!("{}: {}", lang.t("ceiling"), price_limit),
+            PriceControl::Floor => format!("{}: {}", lang.t("floor"), price_limit),
+            PriceControl::Quota => format!("{}: {}", lang.t("quota"), lang.fmt_quantity(unit, self.model.quota)),
+            PriceControl::None => lang.t("no_intervention").to_owned(),
+        };
+        let point = |key, price, quantity| {
+            format!("{}: {}, {}", lang.t(key), lang.fmt_money(currency, price), lang.fmt_quantity(unit, quantity))
+        };
+        [
+            control,
+            format!("{}: {}", lang.t("supply_shift"), lang.fmt_num(self.model.supply_shift)),
+            format!("{}: {}", lang.t("demand_shift"), lang.fmt_num(self.model.demand_shift)),
+            point("equilibrium", result.equilibrium_price, result.equilibrium_quantity),
+            point("traded_point", result.price, result.quantity),
+            format!("{}: {}", lang.t("consumer_surplus"), lang.fmt_money(currency, result.consumer_surplus)),
+            format!("{}: {}", lang.t("producer_surplus"), lang.fmt_money(currency, result.producer_surplus)),
+            format!("{}: {}", lang.t("deadweight_loss"), lang.fmt_money(currency, result.deadweight_loss)),
+        ]
+        .join(" · ")
+    }
+
+    // The graph in sentences, in the chosen language, currency and unit,
+    // for screen readers and as the alt text of an exported picture.
+    fn graph_description(&self) -> String {
+        let lang = self.lang();
+        self.model.describe(
+            |key| lang.t(key),
+            |value| lang.fmt_money(&self.currency, value),
+            |value| lang.fmt_quantity(&self.unit, value),
+        )
+    }
+
+    // The outcome of the market in numbers: surpluses, prices, the split of
+    // a tax and the comparison with the free market.
+    fn show_outcome(&self, ui: &mut egui::Ui, result: &SurplusResult, currency: &str) {
+        let lang = self.lang();
+        let money_label = |key, value| format!("{}: {}", lang.t(key), lang.fmt_money(currency, value));
+        // The state of the market at a glance, before the welfare figures.
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            egui::Grid::new("market_card").num_columns(3).show(ui, |ui| {
+                ui.label("");
+                ui.strong(lang.t("price"));
+                ui.strong(lang.t("quantity"));
+                ui.end_row();
+                ui.label(lang.t("equilibrium"));
+                ui.label(lang.fmt_money(currency, result.equilibrium_price));
+                ui.label(lang.fmt_quantity(&self.unit, result.equilibrium_quantity));
+                ui.end_row();
+                ui.label(lang.t("traded_point"));
+                ui.label(lang.fmt_money(currency, result.price));
+                ui.label(lang.fmt_quantity(&self.unit, result.quantity));
+                ui.end_row();
+            });
+            // A limit at the price the market would trade at without it sits
+            // right at the threshold, about to bite, which the badge alone
+            // hides. With a tax or subsidy that is not the equilibrium price.
+            let limits_price = matches!(self.model.price_control, PriceControl::Ceiling | PriceControl::Floor);
+            let threshold = self.model.binding_threshold().filter(|_| limits_price);
+            if threshold.is_some_and(|threshold| (self.model.price_limit - threshold).abs() < AT_THRESHOLD) {
+                ui.colored_label(egui::Color32::from_rgb(200, 140, 0), lang.t("at_equilibrium"));
+            } else if self.model.price_control != PriceControl::None {
+                if result.binding {
+                    ui.colored_label(egui::Color32::from_rgb(200, 60, 0), lang.t("binding"));
+                } else {
+                    ui.colored_label(egui::Color32::GRAY, lang.t("not_binding"));
+                }
+            }
+        });
+        let baseline = self.model.without_intervention().calculate_surplus();
+        let free_market_total = baseline.total_surplus();
+        // Shares only mean something when the free market achieves anything.
+        let as_share = self.surplus_as_share && free_market_total > f64::EPSILON;
+        let surplus_label = |key, value: f64| {
+            if as_share {
+                format!("{}: {}%", lang.t(key), lang.fmt_num(100.0 * value / free_market_total))
+            } else {
+                money_label(key, value)
+            }
+        };
+        ui.label(surplus_label("consumer_surplus", result.consumer_surplus));
+        ui.label(surplus_label("producer_surplus", result.producer_surplus));
+        ui.label(surplus_label("deadweight_loss", result.deadweight_loss));
+        if result.waiting_cost > 0.0 {
+            ui.label(surplus_label("waiting_cost", result.waiting_cost));
+        }
+        ui.label(surplus_label("total_surplus", result.total_surplus()));
+        // What buyers spend is what sellers take in, unless a tax or subsidy
+        // drives the two prices apart.
+        let (expenditure, revenue) = (result.price * result.quantity, result.seller_price * result.quantity);
+        if result.price == result.seller_price {
+            ui.label(money_label("expenditure_and_revenue", expenditure));
+        } else {
+            ui.label(format!(
+                "{}: {}, {}: {}",
+                lang.t("expenditure"),
+                lang.fmt_money(currency, expenditure),
+                lang.t("revenue"),
+                lang.fmt_money(currency, revenue)
+            ));
+        }
+        if let Some((demand_elasticity, supply_elasticity)) = self.model.elasticities() {
+            ui.label(format!(
+                "{}: {}, {}: {}",
+                lang.t("demand_price_elasticity"),
+                lang.fmt_num(demand_elasticity),
+                lang.t("supply_price_elasticity"),
+                lang.fmt_num(supply_elasticity)
+            ));
+        }
+        if self.model.supply_shift != 0.0 || self.model.demand_shift != 0.0 {
+            // How far the shock moved the free-market equilibrium.
+            let shifted = self.model.find_equilibrium();
+            let unshifted = self.model.without_shifts().find_equilibrium();
+            if let (Some((quantity, price)), Some((base_quantity, base_price))) = (shifted, unshifted) {
+                let percent_change = |new: f64, old: f64| {
+                    if old > f64::EPSILON {
+                        format!("{}%", lang.fmt_signed_num(100.0 * (new - old) / old))
+                    } else {
+                        "–".to_owned()
+                    }
+                };
+                ui.label(format!(
+                    "{}: {}, {}: {}",
+                    lang.t("price_change"),
+                    percent_change(price, base_price),
+                    lang.t("quantity_change"),
+                    percent_change(quantity, base_quantity)
+                ));
+            }
+        }
+        if self.model.tax() > 0.0 {
+            ui.label(money_label("tax_revenue", result.tax_revenue));
+        }
+        if self.model.subsidy() > 0.0 {
+            ui.label(money_label("subsidy_cost", result.subsidy_cost));
+        }
+        if self.model.tax() > 0.0 || self.model.subsidy() > 0.0 {
+            ui.label(format!(
+                "{}: {}, {}: {}",
+                lang.t("buyer_price"),
+                lang.fmt_money(currency, result.price),
+                lang.t("seller_price"),
+                lang.fmt_money(currency, result.seller_price)
+            ));
+        }
+        if let Some((buyers, sellers)) = result.incidence().filter(|_| self.model.tax() > 0.0) {
+            let wedge = buyers + sellers;
+            ui.label(format!(
+                "{}: {} ({}%), {}: {} ({}%)",
+                lang.t("borne_by_buyers"),
+                lang.fmt_money(currency, buyers),
+                lang.fmt_num(100.0 * buyers / wedge),
+                lang.t("borne_by_sellers"),
+                lang.fmt_money(currency, sellers),
+                lang.fmt_num(100.0 * sellers / wedge)
+            ));
+        }
+        egui::Grid::new("baseline_comparison").striped(true).show(ui, |ui| {
+            ui.label("");
+            ui.label(lang.t("regulated"));
+            ui.label(lang.t("free_market"));
+            ui.label("Δ");
+            ui.end_row();
+            for (key, regulated, free_market) in [
+                ("consumer_surplus", result.consumer_surplus, baseline.consumer_surplus),
+                ("producer_surplus", result.producer_surplus, baseline.producer_surplus),
+                ("total_surplus", result.total_surplus(), baseline.total_surplus()),
+            ] {
+                ui.label(lang.t(key));
+                ui.label(lang.fmt_money(currency, regulated));
+                ui.label(lang.fmt_money(currency, free_market));
+                ui.label(lang.fmt_signed_money(currency, regulated - free_market));
+                ui.end_row();
+            }
+        });
+        // The deadweight loss on the scale of the market: a share of what the
+        // free market would achieve, when it achieves anything at all.
+        if free_market_total > f64::EPSILON {
+            ui.label(format!(
+                "{}: {}%",
+                lang.t("efficiency_loss"),
+                lang.fmt_num(100.0 * result.deadweight_loss / free_market_total)
+            ));
+        }
+        // Split the change into what merely changed hands and what is gone,
+        // which only adds up so simply without a tax or subsidy in the way.
+        let untaxed = self.model.tax() == 0.0 && self.model.subsidy() == 0.0;
+        if result.binding && untaxed {
+            ui.label(format!(
+                "{}: {}, {}: {}",
+                lang.t("transfer_to_consumers"),
+                lang.fmt_signed_money(currency, result.transfer_to_consumers()),
+                lang.t("lost_entirely"),
+                lang.fmt_money(currency, baseline.total_surplus() - result.total_surplus())
+            ));
+        }
+    }
+
+    // Arrow keys step the price limit and the chosen shift, ten times as far
+    // with Shift held, unless a text field is taking the keys.
+    fn nudge(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let (horizontal, vertical, step) = ctx.input(|input| {
+            let axis = |plus, minus| input.key_pressed(plus) as i32 - input.key_pressed(minus) as i32;
+            (
+                axis(egui::Key::ArrowRight, egui::Key::ArrowLeft),
+                axis(egui::Key::ArrowUp, egui::Key::ArrowDown),
+                if input.modifiers.shift { 1.0 } else { 0.1 },
+            )
+        });
+        if horizontal != 0 && !self.locks.holds("price_limit") {
+            let limit = self.model.price_limit + horizontal as f64 * step;
+            self.model.price_limit = limit.clamp(0.0, self.y_max);
+        }
+        let shift_key = match self.nudged_shift {
+            Curve::Supply => "supply_shift",
+            Curve::Demand => "demand_shift",
+        };
+        if vertical != 0 && !self.locks.holds(shift_key) {
+            let shift = match (self.nudged_shift, self.animate_shifts) {
+                (Curve::Supply, true) => &mut self.supply_shift_target,
+                (Curve::Supply, false) => &mut self.model.supply_shift,
+                (Curve::Demand, true) => &mut self.demand_shift_target,
+                (Curve::Demand, false) => &mut self.model.demand_shift,
+            };
+            *shift = (*shift + vertical as f64 * step).clamp(-10.0, 10.0);
+        }
+    }
+
+    fn snap_price_limit(&mut self) {
+        if self.snap_price_limit {
+            self.model.price_limit = snap_to_step(self.model.price_limit, self.price_step).min(self.y_max);
+        }
+    }
+
+    // Lowers the ceiling to the ratchet's `step`, stopping the timer at the
+    // last one.
+    fn go_to_ratchet_step(&mut self, step: usize) {
+        if let Some(ratchet) = &mut self.ratchet {
+            ratchet.step = step.min(Ratchet::STEPS.len() - 1);
+            ratchet.elapsed = 0.0;
+            ratchet.playing &= ratchet.step + 1 < Ratchet::STEPS.len();
+            self.model.price_limit = Ratchet::STEPS[ratchet.step].0;
+        }
+    }
+
+    // Moves the lesson to `step`, setting up its market, or leaves it at zero.
+    fn go_to_tutorial_step(&mut self, step: usize) {
+        self.tutorial_step = step;
+        if let Some((_, market)) = step.checked_sub(1).and_then(|index| tutorial_steps().into_iter().nth(index)) {
+            self.model = market;
+            self.settle_shifts();
+        }
+    }
+
+    // Adds the shock to the shifts, easing into it if shifts are animated.
+    fn apply_shock(&mut self, shock: &'static Shock) {
+        if self.animate_shifts {
+            self.supply_shift_target += shock.supply_shift;
+            self.demand_shift_target += shock.demand_shift;
+        } else {
+            self.model.supply_shift += shock.supply_shift;
+            self.model.demand_shift += shock.demand_shift;
+        }
+        self.applied_shock = Some(shock);
+    }
+
+    fn reset(&mut self) {
+        self.model = PriceLimitSimulator::default();
+        self.settle_shifts();
+        self.applied_shock = None;
+        self.reset_plot = true;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_scenario(&mut self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(&self.model)?)?;
+        self.saved_model = self.model.clone();
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_recording(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(&self.recording)?)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_recording(&mut self, path: &str) -> std::io::Result<()> {
+        self.recording = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        self.recording_since = None;
+        self.replay = None;
+        Ok(())
+    }
+
+    // Holds a request to close the window while the market differs from the
+    // scenario last saved or loaded, and asks whether to save it first.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn confirm_close_if_unsaved(&mut self, ctx: &egui::Context) {
+        if ctx.input(|input| input.viewport().close_requested()) && self.model != self.saved_model {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.confirm_close = true;
+            // Leaves room in the dialog for why saving failed, if it does.
+            self.status.clear();
+        }
+        if !self.confirm_close {
+            return;
+        }
+        let lang = self.lang();
+        egui::Window::new(lang.t("unsaved_changes"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!("{} {}?", lang.t("save_before_closing"), self.scenario_path));
+                ui.horizontal(|ui| {
+                    if ui.button(lang.t("save")).clicked() {
+                        let path = self.scenario_path.clone();
+                        match self.save_scenario(&path) {
+                            Ok(()) => {
+                                self.confirm_close = false;
+                                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                            }
+                            Err(err) => self.status = format!("{}: {}", lang.t("save_failed"), err),
+                        }
+                    }
+                    // Closing goes ahead once the market counts as saved.
+                    if ui.button(lang.t("discard")).clicked() {
+                        self.saved_model = self.model.clone();
+                        self.confirm_close = false;
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                    if ui.button(lang.t("cancel")).clicked() {
+                        self.confirm_close = false;
+                    }
+                });
+                if !self.status.is_empty() {
+                    ui.label(&self.status);
+                }
+            });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_scenario(&mut self, path: &str) -> std::io::Result<()> {
+        self.model = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        self.settle_shifts();
+        self.saved_model = self.model.clone();
+        Ok(())
+    }
+
+    // Reads `quantity,price` rows, skipping any that are not two numbers such
+    // as a header, and fits the chosen curve to them as a straight line.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_data(&mut self, path: &str) -> std::io::Result<()> {
+        let observations: Vec<[f64; 2]> = std::fs::read_to_string(path)?
+            .lines()
+            .filter_map(|line| {
+                let (quantity, price) = line.split_once(',')?;
+                Some([quantity.trim().parse().ok()?, price.trim().parse().ok()?])
+            })
+            .collect();
+        let fit = crate::model::fit_line(&observations).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "need at least two different quantities")
+        })?;
+        // The shift sliders keep their place, so the fitted line is what the
+        // curve becomes including its current shift.
+        match self.fitted_curve {
+            Curve::Supply => {
+                let (offset, stretch) = self.model.supply_transform();
+                self.model.supply_model = SupplyModel::Linear;
+                self.model.supply_slope = fit.slope / stretch;
+                self.model.supply_intercept = fit.intercept - offset;
+            }
+            Curve::Demand => {
+                let (offset, stretch) = self.model.demand_transform();
+                self.model.demand_model = DemandModel::Linear;
+                self.model.demand_slope = fit.slope / stretch;
+                self.model.demand_intercept = fit.intercept - offset;
+            }
+        }
+        self.observations = observations;
+        self.observation_fit = Some(fit);
+        Ok(())
+    }
+
+    // Everything above the plots: the header, the market's parameters, its
+    // outcome and the file and view settings. In compact mode the outcome
+    // folds away under a header.
+    fn show_controls(&mut self, ui: &mut egui::Ui, compact: bool) {
+        ui.horizontal(|ui| {
+            ui.label(self.lang().t("title"));
+            if ui.button(self.lang().t("reset")).clicked() {
+                self.reset();
+            }
+            let undo = egui::Button::new(self.lang().t("undo"));
+            if ui.add_enabled(!self.undo_stack.is_empty(), undo).clicked() {
+                self.undo();
+            }
+            let redo = egui::Button::new(self.lang().t("redo"));
+            if ui.add_enabled(!self.redo_stack.is_empty(), redo).clicked() {
+                self.redo();
+            }
+            ui.selectable_value(&mut self.language, Language::En, "EN");
+            ui.selectable_value(&mut self.language, Language::Nl, "NL");
+            // The rest of the row already in the language just chosen.
+            let lang = self.lang();
+            ui.checkbox(&mut self.dark_mode, lang.t("dark_mode"));
+            ui.checkbox(&mut self.colorblind_mode, lang.t("colorblind_mode"));
+            ui.checkbox(&mut self.compact, lang.t("compact"));
+            let help = ui.toggle_value(&mut self.show_help, "?");
+            describe(help, &format!("{} (F1)", lang.t("help_title")), self.show_help);
+            let settings = ui.toggle_value(&mut self.show_settings, "⚙");
+            describe(settings, lang.t("settings"), self.show_settings);
+        });
+        let lang = self.lang();
+        ui.horizontal(|ui| {
+            ui.label(lang.t("preset"));
+            // Whichever preset the sliders still match is shown as active.
+            let active = presets().into_iter().find(|(_, preset)| *preset == self.model);
+            egui::ComboBox::from_id_salt("preset")
+                .selected_text(lang.t(active.map_or("preset_custom", |(name, _)| name)))
+                .show_ui(ui, |ui| {
+                    for (name, preset) in presets() {
+                        if ui.selectable_label(self.model == preset, lang.t(name)).clicked() {
+                            self.model = preset;
+                            self.settle_shifts();
+                        }
+                    }
+                });
+            if self.tutorial_step == 0 && ui.button(lang.t("tutorial")).clicked() {
+                self.go_to_tutorial_step(1);
+            }
+            ui.menu_button(lang.t("shocks"), |ui| {
+                for shock in &SHOCKS {
+                    if ui.button(lang.t(shock.name)).on_hover_text(lang.t(shock.caption)).clicked() {
+                        self.apply_shock(shock);
+                        ui.close_menu();
+                    }
+                }
+            });
+            if self.ratchet.is_none() && ui.button(lang.t("ratchet")).clicked() {
+                self.model = PriceLimitSimulator { price_limit: Ratchet::STEPS[0].0, ..Default::default() };
+                self.settle_shifts();
+                self.ratchet = Some(Ratchet { step: 0, elapsed: 0.0, playing: true });
+            }
+            let mut recording = self.recording_since.is_some();
+            let record = egui::Checkbox::new(&mut recording, lang.t("record"));
+            if ui.add_enabled(self.replay.is_none(), record).changed() {
+                if recording {
+                    self.recording.clear();
+                    self.recording_since = Some(ui.input(|input| input.time));
+                } else {
+                    self.recording_since = None;
+                }
+            }
+            if self.replay.is_some() {
+                if ui.button(lang.t("stop")).clicked() {
+                    self.replay = None;
+                }
+            } else {
+                let can_play = !recording && !self.recording.is_empty();
+                if ui.add_enabled(can_play, egui::Button::new(lang.t("play_recording"))).clicked() {
+                    self.replay = Some(Replay { elapsed: 0.0, next: 0 });
+                }
+            }
+            if !self.recording.is_empty() {
+                ui.label(format!("{} {}", self.recording.len(), lang.t("changes_recorded")));
+            }
+        });
+        if let Some(shock) = self.applied_shock {
+            ui.horizontal(|ui| {
+                ui.label(lang.t(shock.caption));
+                if ui.small_button("×").on_hover_text(lang.t("close")).clicked() {
+                    self.applied_shock = None;
+                }
+            });
+        }
+        if let Some(ratchet) = &self.ratchet {
+            let (step, playing) = (ratchet.step, ratchet.playing);
+            let last = step + 1 == Ratchet::STEPS.len();
+            egui::Frame::group(ui.style()).show(ui, |ui| {
+                ui.strong(format!("{} {}/{}", lang.t("ratchet"), step + 1, Ratchet::STEPS.len()));
+                ui.label(lang.t(Ratchet::STEPS[step].1));
+                ui.horizontal(|ui| {
+                    let pause_or_play = if playing { lang.t("pause") } else { lang.t("play") };
+                    if ui.add_enabled(!last, egui::Button::new(pause_or_play)).clicked() {
+                        if let Some(ratchet) = &mut self.ratchet {
+                            ratchet.playing = !playing;
+                        }
+                    }
+                    if ui.add_enabled(!last, egui::Button::new(lang.t("next"))).clicked() {
+                        self.go_to_ratchet_step(step + 1);
+                    }
+                    if ui.button(lang.t("close")).clicked() {
+                        self.ratchet = None;
+                    }
+                });
+            });
+        }
+        if self.tutorial_step > 0 {
+            let steps = tutorial_steps();
+            egui::Frame::group(ui.style()).show(ui, |ui| {
+                ui.strong(format!("{} {}/{}", lang.t("tutorial"), self.tutorial_step, steps.len()));
+                ui.label(lang.t(steps[self.tutorial_step - 1].0));
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(self.tutorial_step > 1, egui::Button::new(lang.t("back"))).clicked() {
+                        self.go_to_tutorial_step(self.tutorial_step - 1);
+                    }
+                    let next = egui::Button::new(lang.t("next"));
+                    if ui.add_enabled(self.tutorial_step < steps.len(), next).clicked() {
+                        self.go_to_tutorial_step(self.tutorial_step + 1);
+                    }
+                    if ui.button(lang.t("end_tutorial")).clicked() {
+                        self.go_to_tutorial_step(0);
+                    }
+                });
+            });
+        }
+        let model = &mut self.model;
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut model.price_control, PriceControl::Ceiling, lang.t("ceiling"));
+            ui.radio_value(&mut model.price_control, PriceControl::Floor, lang.t("floor"));
+            ui.radio_value(&mut model.price_control, PriceControl::Quota, lang.t("quota"));
+            ui.radio_value(&mut model.price_control, PriceControl::None, lang.t("none"));
+            let clear = egui::Button::new(lang.t("clear")).small();
+            let clear = ui.add_enabled(model.price_control != PriceControl::None, clear);
+            if clear.on_hover_text(lang.t("clear_control")).clicked() {
+                model.relax_control();
+            }
+        });
+        if model.price_control == PriceControl::Quota {
+            self.locks.slider(ui, &mut model.quota, 0.0..=self.x_max, "quota", lang);
+            if let Some(threshold) = model.quota_threshold() {
+                let threshold_label = format!("{}: {}", lang.t("binding_threshold"), lang.fmt_num(threshold));
+                binding_track(ui, threshold, self.x_max, true).on_hover_text(&threshold_label);
+                ui.label(threshold_label);
+            }
+            let standing = match model.quota_standing() {
+                Some(QuotaStanding::AtThreshold) => Some("quota_at_threshold"),
+                Some(QuotaStanding::NotBinding) => Some("quota_not_binding"),
+                Some(QuotaStanding::Binding) | None => None,
+            };
+            if let Some(key) = standing {
+                ui.label(lang.t(key));
+            }
+        } else {
+            let slid = self.locks.slider(ui, &mut model.price_limit, 0.0..=self.y_max, "price_limit", lang);
+            let limits_price = matches!(model.price_control, PriceControl::Ceiling | PriceControl::Floor);
+            if let Some(threshold) = model.binding_threshold().filter(|_| limits_price) {
+                let threshold_label =
+                    format!("{}: {}", lang.t("binding_threshold"), lang.fmt_money(&self.currency, threshold));
+                binding_track(ui, threshold, self.y_max, model.price_control == PriceControl::Ceiling)
+                    .on_hover_text(&threshold_label);
+                ui.label(threshold_label);
+            }
+            ui.horizontal(|ui| {
+                let snap = ui.checkbox(&mut self.snap_price_limit, lang.t("snap_to"));
+                let step = egui::DragValue::new(&mut self.price_step).range(0.05..=5.0).speed(0.05);
+                ui.add_enabled(self.snap_price_limit, step).labelled_by(snap.id);
+            });
+            if slid.changed() && self.snap_price_limit {
+                model.price_limit = snap_to_step(model.price_limit, self.price_step).min(self.y_max);
+            }
+        }
+        if model.price_control == PriceControl::Ceiling {
+            let equilibrium = model.find_equilibrium();
+            let can_lift =
+                self.ceiling_lift.is_none() && equilibrium.is_some() && model.calculate_surplus().binding;
+            if ui.add_enabled(can_lift, egui::Button::new(lang.t("lift_ceiling"))).clicked() {
+                if let Some((_, equilibrium_price)) = equilibrium {
+                    self.ceiling_lift = Some(CeilingLift {
+                        from: model.price_limit,
+                        to: (equilibrium_price + 2.0).min(self.y_max),
+                        elapsed: 0.0,
+                        current: model.price_limit,
+                    });
+                }
+            }
+            let market = PriceLimitSimulator { price_limit: 0.0, ..model.clone() };
+            ui.horizontal(|ui| {
+                if ui.button(lang.t("best_ceiling")).clicked() {
+                    let (price_limit, consumer_surplus) = market.best_ceiling_for_consumers();
+                    let market = market.clone();
+                    self.best_ceiling = Some(BestCeiling { market, price_limit, consumer_surplus });
+                }
+                if let Some(best) = self.best_ceiling.as_ref().filter(|best| best.market == market) {
+                    ui.label(format!(
+                        "{}, {}: {}",
+                        lang.fmt_money(&self.currency, best.price_limit),
+                        lang.t("consumer_surplus"),
+                        lang.fmt_money(&self.currency, best.consumer_surplus)
+                    ));
+                    if ui.button(lang.t("apply")).clicked() {
+                        model.price_limit = best.price_limit;
+                    }
+                }
+            });
+            self.locks.slider(ui, &mut model.waiting_cost_fraction, 0.0..=1.0, "waiting_cost_fraction", lang);
+        }
+        // The amount of the levy switched off stays in the model, so that
+        // switching back restores it.
+        let mut levy = model.active_levy();
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut levy, Levy::Tax, lang.t("tax"));
+            ui.radio_value(&mut levy, Levy::Subsidy, lang.t("subsidy"));
+            ui.radio_value(&mut levy, Levy::None, lang.t("none"));
+            let clear = ui.add_enabled(levy != Levy::None, egui::Button::new(lang.t("clear")).small());
+            if clear.on_hover_text(lang.t("clear_levy")).clicked() {
+                model.clear_levy();
+            } else if levy != model.active_levy() {
+                model.levy = levy;
+            }
+        });
+        match model.active_levy() {
+            Levy::Tax => {
+                self.locks.slider(ui, &mut model.tax_per_unit, 0.0..=10.0, "tax_per_unit", lang);
+            }
+            Levy::Subsidy => {
+                self.locks.slider(ui, &mut model.subsidy_per_unit, 0.0..=10.0, "subsidy_per_unit", lang);
+            }
+            Levy::Auto | Levy::None => {}
+        }
+        ui.horizontal(|ui| {
+            if ui.radio(model.supply_model == SupplyModel::Linear, lang.t("linear")).clicked() {
+                model.supply_model = SupplyModel::Linear;
+            }
+            let quadratic = matches!(model.supply_model, SupplyModel::Quadratic { .. });
+            if ui.radio(quadratic, lang.t("quadratic")).clicked() && !quadratic {
+                model.supply_model = SupplyModel::Quadratic { a: 0.05, b: 0.2, c: 5.0 };
+            }
+            if ui.radio(model.supply_model == SupplyModel::Custom, lang.t("custom_formula")).clicked() {
+                model.supply_model = SupplyModel::Custom;
+            }
+        });
+        match &mut model.supply_model {
+            SupplyModel::Linear => {
+                self.locks.slider(ui, &mut model.supply_slope, 0.1..=5.0, "supply_slope", lang);
+                self.locks.slider(ui, &mut model.supply_intercept, 0.0..=15.0, "supply_intercept", lang);
+            }
+            SupplyModel::Quadratic { a, b, c } => {
+                self.locks.slider(ui, a, 0.0..=1.0, "supply_curvature", lang);
+                self.locks.slider(ui, b, 0.0..=5.0, "supply_linear_term", lang);
+                self.locks.slider(ui, c, 0.0..=15.0, "supply_constant", lang);
+            }
+            SupplyModel::Custom => {
+                let input = &mut self.supply_input;
+                formula_input(ui, lang, lang.t("supply_formula"), input, &mut model.supply_expression);
+            }
+        }
+        if self.animate_shifts {
+            self.locks.slider(ui, &mut self.supply_shift_target, -10.0..=10.0, "supply_shift", lang);
+        } else {
+            self.locks.slider(ui, &mut model.supply_shift, -10.0..=10.0, "supply_shift", lang);
+        }
+        ui.horizontal(|ui| {
+            if ui.radio(model.demand_model == DemandModel::Linear, lang.t("linear")).clicked() {
+                model.demand_model = DemandModel::Linear;
+            }
+            let elastic = matches!(model.demand_model, DemandModel::ConstantElasticity { .. });
+            if ui.radio(elastic, lang.t("constant_elasticity")).clicked() && !elastic {
+                model.demand_model = DemandModel::ConstantElasticity { a: 50.0, epsilon: 1.0 };
+            }
+            if ui.radio(model.demand_model == DemandModel::Custom, lang.t("custom_formula")).clicked() {
+                model.demand_model = DemandModel::Custom;
+            }
+            let grouped = matches!(model.demand_model, DemandModel::TwoGroups { .. });
+            if ui.radio(grouped, lang.t("two_groups")).clicked() && !grouped {
+                model.demand_model = DemandModel::TwoGroups { slope: -1.5, intercept: 10.0 };
+            }
+        });
+        match &mut model.demand_model {
+            DemandModel::Linear => {
+                self.locks.slider(ui, &mut model.demand_slope, -5.0..=-0.1, "demand_slope", lang);
+                self.locks.slider(ui, &mut model.demand_intercept, 0.0..=30.0, "demand_intercept", lang);
+            }
+            DemandModel::TwoGroups { slope, intercept } => {
+                self.locks.slider(ui, &mut model.demand_slope, -5.0..=-0.1, "demand_slope", lang);
+                self.locks.slider(ui, &mut model.demand_intercept, 0.0..=30.0, "demand_intercept", lang);
+                self.locks.slider(ui, slope, -5.0..=-0.1, "second_group_slope", lang);
+                self.locks.slider(ui, intercept, 0.0..=30.0, "second_group_intercept", lang);
+            }
+            DemandModel::ConstantElasticity { a, epsilon } => {
+                self.locks.slider(ui, a, 1.0..=200.0, "demand_scale", lang);
+                self.locks.slider(ui, epsilon, 0.2..=5.0, "demand_elasticity", lang);
+            }
+            DemandModel::Custom => {
+                let input = &mut self.demand_input;
+                formula_input(ui, lang, lang.t("demand_formula"), input, &mut model.demand_expression);
+            }
+        }
+        if self.animate_shifts {
+            self.locks.slider(ui, &mut self.demand_shift_target, -10.0..=10.0, "demand_shift", lang);
+        } else {
+            self.locks.slider(ui, &mut model.demand_shift, -10.0..=10.0, "demand_shift", lang);
+        }
+        ui.horizontal(|ui| {
+            ui.label(lang.t("shift_mode"));
+            ui.radio_value(&mut model.shift_mode, ShiftMode::Parallel, lang.t("parallel_shift"));
+            ui.radio_value(&mut model.shift_mode, ShiftMode::Rotate, lang.t("rotate_shift"));
+        });
+        if self.shift_history.len() > 1 {
+            self.show_shift_history(ui);
+        }
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.animate_shifts, lang.t("animate_shifts"));
+            ui.label(lang.t("arrow_keys_shift"));
+            ui.radio_value(&mut self.nudged_shift, Curve::Supply, lang.t("supply_curve"));
+            ui.radio_value(&mut self.nudged_shift, Curve::Demand, lang.t("demand_curve"));
+        });
+
+        // Without a crossing the surplus numbers mean nothing, so they are
+        // left out and the areas are not drawn.
+        let valid = self.model.is_valid();
+        if self.model.near_vertical() {
+            ui.colored_label(egui::Color32::RED, lang.t("near_vertical"));
+        } else if !valid {
+            ui.colored_label(egui::Color32::RED, lang.t("no_equilibrium"));
+        }
+
+        let (currency, unit) = (self.currency.clone(), self.unit.clone());
+        let (currency, unit) = (currency.as_str(), unit.as_str());
+        let result = self.model.calculate_surplus();
+        let quantity_demanded = self.model.demand_quantity(result.price);
+        let quantity_supplied = result.quantity;
+        let shortage = (quantity_demanded - quantity_supplied).max(0.0);
+        if valid {
+            let outcome = |ui: &mut egui::Ui| {
+                self.show_outcome(ui, &result, currency);
+                ui.label(format!("{}: {}", lang.t("shortage"), lang.fmt_quantity(unit, shortage)));
+                if self.model.price_control == PriceControl::Floor {
+                    let quantity_supplied = self.model.supply_quantity(result.seller_price);
+                    ui.label(format!(
+                        "{}: {}",
+                        lang.t("excess_supply"),
+                        lang.fmt_quantity(unit, (quantity_supplied - result.quantity).max(0.0))
+                    ));
+                }
+                // What each side would trade at the limit, whether or not it
+                // binds, whose gap is the shortage or excess supply. Sellers
+                // receive the limit less any tax, plus any subsidy.
+                if self.show_price_quantities {
+                    let model = &self.model;
+                    let price = match model.price_control {
+                        PriceControl::Ceiling | PriceControl::Floor => model.price_limit.max(0.0),
+                        PriceControl::Quota | PriceControl::None => result.price,
+                    };
+                    let seller_price = price - model.tax() + model.subsidy();
+                    ui.label(format!("{} {}:", lang.t("at_price"), lang.fmt_money(currency, price)));
+                    for (key, quantity) in [
+                        ("quantity_supplied", model.supply_quantity(seller_price)),
+                        ("quantity_demanded", model.demand_quantity(price)),
+                    ] {
+                        ui.label(format!("{}: {}", lang.t(key), lang.fmt_quantity(unit, quantity)));
+                    }
+                }
+            };
+            if compact {
+                egui::CollapsingHeader::new(lang.t("outcome")).default_open(true).show(ui, outcome);
+            } else {
+                outcome(ui);
+            }
+        }
+        egui::CollapsingHeader::new(lang.t("graph_description")).show(ui, |ui| {
+            ui.label(self.graph_description());
+        });
+        if self.show_equations {
+            let (supply_equation, demand_equation) = curve_equations(&self.model, lang);
+            ui.group(|ui| {
+                ui.monospace(supply_equation);
+                ui.monospace(demand_equation);
+                if let Some((quantity, price)) = self.model.find_equilibrium() {
+                    ui.monospace(format!("q* = {}, p* = {}", lang.fmt_num(quantity), lang.fmt_num(price)));
+                }
+                if valid {
+                    for line in binding_derivation(&self.model, &result, lang) {
+                        ui.monospace(line);
+                    }
+                }
+            });
+        }
+
+        // Browsers give the app no file system to write to.
+        #[cfg(not(target_arch = "wasm32"))]
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.export_path);
+            if ui.button(lang.t("export_csv")).clicked() {
+                self.status = match self.export_csv(&self.export_path) {
+                    Ok(()) => format!("{} {}", lang.t("exported_to"), self.export_path),
+                    Err(err) => format!("{}: {}", lang.t("export_failed"), err),
+                };
+            }
+        });
+        #[cfg(not(target_arch = "wasm32"))]
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.sweep_path);
+            if ui.button(lang.t("export_sweep")).clicked() {
+                self.status = match self.export_sweep_csv(&self.sweep_path) {
+                    Ok(()) => format!("{} {}", lang.t("exported_to"), self.sweep_path),
+                    Err(err) => format!("{}: {}", lang.t("export_failed"), err),
+                };
+            }
+        });
+        // The screenshot arrives as an event in a later frame, and is
+        // written out from there.
+        #[cfg(not(target_arch = "wasm32"))]
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.png_path);
+            if ui.button(lang.t("export_png")).clicked() {
+                ui.ctx().send_viewport_cmd(egui::ViewportCommand::Screenshot(egui::UserData::default()));
+            }
+        });
+        // The clipboard works in the browser too.
+        ui.horizontal(|ui| {
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                ui.text_edit_singleline(&mut self.latex_path);
+                if ui.button(lang.t("export_latex")).clicked() {
+                    let latex = surplus_latex(&self.model, &self.model.calculate_surplus());
+                    self.status = match std::fs::write(&self.latex_path, latex) {
+                        Ok(()) => format!("{} {}", lang.t("exported_to"), self.latex_path),
+                        Err(err) => format!("{}: {}", lang.t("export_failed"), err),
+                    };
+                }
+            }
+            if ui.button(lang.t("copy_latex")).clicked() {
+                ui.ctx().copy_text(surplus_latex(&self.model, &self.model.calculate_surplus()));
+                self.status = lang.t("copied_latex").to_owned();
+            }
+            if ui.button(lang.t("copy_summary")).clicked() {
+                ui.ctx().copy_text(self.summary_line(&self.model.calculate_surplus()));
+                self.status = lang.t("copied_summary").to_owned();
+            }
+        });
+        #[cfg(not(target_arch = "wasm32"))]
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.scenario_path);
+            if ui.button(lang.t("save_scenario")).clicked() {
+                let path = self.scenario_path.clone();
+                self.status = match self.save_scenario(&path) {
+                    Ok(()) => format!("{} {}", lang.t("saved_to"), self.scenario_path),
+                    Err(err) => format!("{}: {}", lang.t("save_failed"), err),
+                };
+            }
+            if ui.button(lang.t("load_scenario")).clicked() {
+                let path = self.scenario_path.clone();
+                self.status = match self.load_scenario(&path) {
+                    Ok(()) => {
+                        ui.ctx().request_repaint();
+                        format!("{} {}", lang.t("loaded"), path)
+                    }
+                    Err(err) => format!("{}: {}", lang.t("load_failed"), err),
+                };
+            }
+        });
+        #[cfg(not(target_arch = "wasm32"))]
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.recording_path);
+            if ui.add_enabled(!self.recording.is_empty(), egui::Button::new(lang.t("save_recording"))).clicked() {
+                self.status = match self.save_recording(&self.recording_path) {
+                    Ok(()) => format!("{} {}", lang.t("saved_to"), self.recording_path),
+                    Err(err) => format!("{}: {}", lang.t("save_failed"), err),
+                };
+            }
+            if ui.button(lang.t("load_recording")).clicked() {
+                let path = self.recording_path.clone();
+                self.status = match self.load_recording(&path) {
+                    Ok(()) => format!("{} {}", lang.t("loaded"), path),
+                    Err(err) => format!("{}: {}", lang.t("load_failed"), err),
+                };
+            }
+        });
+        #[cfg(not(target_arch = "wasm32"))]
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.data_path);
+            ui.radio_value(&mut self.fitted_curve, Curve::Supply, lang.t("supply_curve"));
+            ui.radio_value(&mut self.fitted_curve, Curve::Demand, lang.t("demand_curve"));
+            if ui.button(lang.t("load_data")).clicked() {
+                let path = self.data_path.clone();
+                self.status = match self.load_data(&path) {
+                    Ok(()) => format!("{} {}", lang.t("loaded"), path),
+                    Err(err) => format!("{}: {}", lang.t("load_failed"), err),
+                };
+            }
+        });
+        if let Some(fit) = self.observation_fit {
+            ui.label(format!(
+                "R²: {}, {}: {}",
+                lang.fmt_num(fit.r_squared),
+                lang.t("standard_error"),
+                lang.fmt_num(fit.standard_error)
+            ));
+        }
+        if !self.status.is_empty() {
+            ui.label(&self.status);
+        }
+        ui.horizontal(|ui| {
+            if ui.button(lang.t("snapshot")).clicked() {
+                self.snapshot = Some(self.model.clone());
+            }
+            if ui.add_enabled(self.snapshot.is_some(), egui::Button::new(lang.t("clear_snapshot"))).clicked() {
+                self.snapshot = None;
+            }
+        });
+        egui::CollapsingHeader::new(lang.t("gallery")).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.gallery_name);
+                if ui.button(lang.t("add_to_gallery")).clicked() {
+                    let mut name = std::mem::take(&mut self.gallery_name);
+                    if name.trim().is_empty() {
+                        name = format!("{} {}", lang.t("scenario"), self.gallery.len() + 1);
+                    }
+                    self.gallery.push(GalleryEntry { name, market: self.model.clone(), shown: true });
+                }
+            });
+            let mut opened = None;
+            let mut removed = None;
+            for (i, entry) in self.gallery.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    let color = GALLERY_COLORS[i % GALLERY_COLORS.len()];
+                    ui.checkbox(&mut entry.shown, egui::RichText::new(&entry.name).color(color));
+                    if ui.small_button(lang.t("open")).clicked() {
+                        opened = Some(entry.market.clone());
+                    }
+                    if ui.small_button("×").on_hover_text(lang.t("remove")).clicked() {
+                        removed = Some(i);
+                    }
+                });
+            }
+            if let Some(market) = opened {
+                self.model = market;
+                self.settle_shifts();
+            }
+            if let Some(i) = removed {
+                self.gallery.remove(i);
+            }
+        });
+        egui::CollapsingHeader::new(lang.t("surplus_records")).show(ui, |ui| {
+            // Each record with what would have to change to get back to it.
+            let mut opened = None;
+            for (key, record) in [("lowest", &self.lowest_surplus), ("highest", &self.highest_surplus)] {
+                let Some(record) = record else { continue };
+                ui.horizontal(|ui| {
+                    let total_surplus = lang.fmt_money(&self.currency, record.total_surplus);
+                    ui.strong(format!("{}: {}", lang.t(key), total_surplus));
+                    if ui.small_button(lang.t("open")).clicked() {
+                        opened = Some(record.market.clone());
+                    }
+                });
+                let changes = self.model.parameter_changes(&record.market);
+                if changes.is_empty() {
+                    ui.label(lang.t("reached_here"));
+                }
+                egui::Grid::new(("surplus_record", key)).show(ui, |ui| {
+                    for change in changes {
+                        ui.monospace(&change.name);
+                        ui.label(fmt_value(lang, &change.to));
+                        ui.end_row();
+                    }
+                });
+            }
+            if let Some(market) = opened {
+                self.model = market;
+                self.settle_shifts();
+            }
+            if ui.button(lang.t("reset_records")).clicked() {
+                self.lowest_surplus = None;
+                self.highest_surplus = None;
+            }
+        });
+        if let Some(snapshot) = &self.snapshot {
+            egui::CollapsingHeader::new(lang.t("changes_since_snapshot")).show(ui, |ui| {
+                let changes = snapshot.parameter_changes(&self.model);
+                if changes.is_empty() {
+                    ui.label(lang.t("no_changes"));
+                }
+                let (before, after) = (snapshot.calculate_surplus(), self.model.calculate_surplus());
+                egui::Grid::new("snapshot_changes").striped(true).show(ui, |ui| {
+                    for change in changes {
+                        ui.monospace(&change.name);
+                        let (from, to) = (fmt_value(lang, &change.from), fmt_value(lang, &change.to));
+                        let mut text = format!("{} → {}", from, to);
+                        if let (Some(from), Some(to)) = (change.from.as_f64(), change.to.as_f64()) {
+                            text.push_str(&format!(" ({})", lang.fmt_signed_num(to - from)));
+                        }
+                        ui.label(text);
+                        ui.end_row();
+                    }
+                    for (key, from, to) in [
+                        ("consumer_surplus", before.consumer_surplus, after.consumer_surplus),
+                        ("producer_surplus", before.producer_surplus, after.producer_surplus),
+                        ("deadweight_loss", before.deadweight_loss, after.deadweight_loss),
+                        ("total_surplus", before.total_surplus(), after.total_surplus()),
+                    ] {
+                        ui.label(lang.t(key));
+                        ui.label(format!(
+                            "{} → {} ({})",
+                            lang.fmt_money(&self.currency, from),
+                            lang.fmt_money(&self.currency, to),
+                            lang.fmt_signed_money(&self.currency, to - from)
+                        ));
+                        ui.end_row();
+                    }
+                });
+            });
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            ui.checkbox(&mut self.show_supply, lang.t("supply_curve"));
+            ui.checkbox(&mut self.show_demand, lang.t("demand_curve"));
+            ui.checkbox(&mut self.show_price_limit, lang.t("price_limit"));
+            ui.checkbox(&mut self.show_consumer_surplus, lang.t("consumer_surplus"));
+            ui.checkbox(&mut self.show_producer_surplus, lang.t("producer_surplus"));
+            ui.checkbox(&mut self.show_deadweight_loss, lang.t("deadweight_loss"));
+            ui.checkbox(&mut self.show_welfare_sweep, lang.t("welfare_sweep"));
+            ui.checkbox(&mut self.show_tax_sweep, lang.t("tax_sweep"));
+            ui.checkbox(&mut self.show_breakdown, lang.t("surplus_breakdown"));
+            ui.checkbox(&mut self.surplus_as_share, lang.t("surplus_as_share"));
+            ui.checkbox(&mut self.show_monte_carlo, lang.t("monte_carlo"));
+            ui.checkbox(&mut self.show_substitute, lang.t("substitute_market"));
+            ui.checkbox(&mut self.show_grid, lang.t("grid"));
+            ui.checkbox(&mut self.show_equations, lang.t("equations"));
+            ui.checkbox(&mut self.show_dimensions, lang.t("dimensions"));
+            ui.checkbox(&mut self.show_guides, lang.t("guides"));
+            ui.checkbox(&mut self.show_price_quantities, lang.t("price_quantities"));
+            ui.checkbox(&mut self.show_interventions, lang.t("compare_interventions"));
+            ui.checkbox(&mut self.show_weighted_welfare, lang.t("weighted_welfare"));
+            ui.checkbox(&mut self.show_trapezoids, lang.t("trapezoids"));
+            ui.checkbox(&mut self.log_axes, lang.t("log_scale"));
+            ui.add_enabled(!self.log_axes, egui::Checkbox::new(&mut self.transposed, lang.t("quantity_on_y")));
+        });
+        if self.show_trapezoids {
+            ui.horizontal(|ui| {
+                let label = ui.label(lang.t("trapezoid_steps"));
+                let steps = egui::Slider::new(&mut self.trapezoid_steps, 1..=200).logarithmic(true);
+                ui.add(steps).labelled_by(label.id);
+            });
+            // How close that many trapezoids come to the areas as reported.
+            let (consumer, producer) = self.model.surplus_trapezoids(self.trapezoid_steps);
+            let result = self.model.calculate_surplus();
+            for (key, trapezoids, reported) in [
+                ("consumer_surplus", consumer, result.consumer_surplus),
+                ("producer_surplus", producer, result.producer_surplus),
+            ] {
+                let estimate: f64 = trapezoids.iter().map(trapezoid_area).sum();
+                ui.label(format!(
+                    "{}: {} ≈ {}",
+                    lang.t(key),
+                    lang.fmt_money(&self.currency, estimate),
+                    lang.fmt_money(&self.currency, reported)
+                ));
+            }
+        }
+        if self.show_interventions {
+            slider_with_input(ui, &mut self.intervention_severity, 0.0..=0.9, lang.t("severity"));
+            if let Some(matched) = self.model.matched_interventions(self.intervention_severity) {
+                let ranked = matched.ranked();
+                ui.label(format!("{}: {}", lang.t("highest_total_surplus"), lang.t(ranked[0].0)));
+                egui::Grid::new("ranked_interventions").show(ui, |ui| {
+                    for (rank, (key, outcome)) in ranked.iter().enumerate() {
+                        ui.label(format!("{}.", rank + 1));
+                        ui.label(lang.t(key));
+                        ui.label(lang.fmt_money(&self.currency, outcome.total_surplus()));
+                        ui.end_row();
+                    }
+                });
+            }
+        }
+        if self.show_weighted_welfare {
+            slider_with_input(ui, &mut self.welfare_weight, 0.0..=1.0, lang.t("consumer_weight"));
+            let weight = self.welfare_weight;
+            let result = self.model.calculate_surplus();
+            ui.label(format!(
+                "{}: {}",
+                lang.t("weighted_welfare"),
+                lang.fmt_money(&self.currency, result.weighted_welfare(weight))
+            ));
+            let (price_limit, welfare) = self.model.best_ceiling_for_weight(weight);
+            match self.model.find_equilibrium() {
+                Some((_, price)) if price_limit < price => ui.label(format!(
+                    "{}: {} ({})",
+                    lang.t("best_ceiling_at_weight"),
+                    lang.fmt_money(&self.currency, price_limit),
+                    lang.fmt_money(&self.currency, welfare)
+                )),
+                _ => ui.label(lang.t("no_ceiling_helps")),
+            };
+        }
+        if self.show_monte_carlo {
+            ui.horizontal_wrapped(|ui| {
+                let label = ui.label(lang.t("shift_std_dev"));
+                let std_dev = egui::DragValue::new(&mut self.shift_std_dev).range(0.0..=5.0).speed(0.05);
+                ui.add(std_dev).labelled_by(label.id);
+                let label = ui.label(lang.t("draws"));
+                let draws = egui::DragValue::new(&mut self.monte_carlo_draws).range(10..=5000).speed(10.0);
+                ui.add(draws).labelled_by(label.id);
+                for measure in [DrawnMeasure::EquilibriumPrice, DrawnMeasure::ConsumerSurplus] {
+                    ui.radio_value(&mut self.monte_carlo_measure, measure, lang.t(measure.key()));
+                }
+            });
+        }
+        ui.horizontal(|ui| {
+            ui.label(lang.t("domain"));
+            ui.radio_value(&mut self.domain, Domain::Goods, lang.t("goods_market"));
+            ui.radio_value(&mut self.domain, Domain::Labor, lang.t("labor_market"));
+        });
+        ui.horizontal(|ui| {
+            let label = ui.label(lang.t("currency"));
+            ui.add(egui::TextEdit::singleline(&mut self.currency).desired_width(30.0)).labelled_by(label.id);
+            let label = ui.label(lang.t("unit"));
+            ui.add(egui::TextEdit::singleline(&mut self.unit).desired_width(50.0)).labelled_by(label.id);
+            let label = ui.label(lang.t("x_max"));
+            let x_max = egui::DragValue::new(&mut self.x_max).range(1.0..=MAX_QUANTITY).speed(0.5);
+            ui.add(x_max).labelled_by(label.id);
+            let label = ui.label(lang.t("y_max"));
+            ui.add(egui::DragValue::new(&mut self.y_max).range(1.0..=100.0).speed(0.5)).labelled_by(label.id);
+            ui.add(egui::Slider::new(&mut self.area_opacity, 0.0..=1.0).text(lang.t("area_opacity")));
+        });
+        ui.horizontal(|ui| {
+            if ui.button(lang.t("fit_view")).clicked() {
+                self.fit_view = true;
+            }
+            if ui.button(lang.t("reset_zoom")).clicked() {
+                self.reset_plot = true;
+            }
+            ui.checkbox(&mut self.show_legend, lang.t("legend"));
+            ui.add_enabled_ui(self.show_legend, |ui| {
+                egui::ComboBox::from_id_salt("legend_corner")
+                    .selected_text(lang.t(corner_name(self.legend_corner)))
+                    .show_ui(ui, |ui| {
+                        for corner in Corner::all() {
+                            ui.selectable_value(&mut self.legend_corner, corner, lang.t(corner_name(corner)));
+                        }
+                    });
+            });
+        });
+
+        // The substitute's own demand shift stays as set; the spillover
+        // from the main market comes on top.
+        let spillover = self.model.spillover(self.cross_price);
+        let substitute = PriceLimitSimulator {
+            demand_shift: self.substitute.demand_shift + spillover,
+            ..self.substitute.clone()
+        };
+        if self.show_substitute {
+            egui::CollapsingHeader::new(lang.t("substitute_market")).default_open(true).show(ui, |ui| {
+                let market = &mut self.substitute;
+                slider_with_input(ui, &mut self.cross_price, 0.0..=2.0, lang.t("cross_price"));
+                ui.checkbox(&mut self.normalize_prices, lang.t("normalize_prices"));
+                slider_with_input(ui, &mut market.supply_slope, 0.1..=5.0, lang.t("supply_slope"));
+                slider_with_input(ui, &mut market.supply_intercept, 0.0..=15.0, lang.t("supply_intercept"));
+                slider_with_input(ui, &mut market.demand_slope, -5.0..=-0.1, lang.t("demand_slope"));
+                slider_with_input(ui, &mut market.demand_intercept, 0.0..=30.0, lang.t("demand_intercept"));
+                ui.label(format!("{}: {}", lang.t("spillover"), lang.fmt_signed_money(currency, spillover)));
+                if let (Some((quantity, price)), Some((base_quantity, base_price))) =
+                    (substitute.find_equilibrium(), market.find_equilibrium())
+                {
+                    ui.label(format!(
+                        "{}: {} ({}), {}: {} ({})",
+                        lang.t("price"),
+                        lang.fmt_money(currency, price),
+                        lang.fmt_signed_money(currency, price - base_price),
+                        lang.t("quantity"),
+                        lang.fmt_quantity(unit, quantity),
+                        lang.fmt_signed_num(quantity - base_quantity)
+                    ));
+                }
+            });
+        }
+
+        egui::CollapsingHeader::new(lang.t("curve_values")).show(ui, |ui| {
+            egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                egui::Grid::new("curve_values").striped(true).show(ui, |ui| {
+                    ui.strong(lang.t("quantity"));
+                    ui.strong(lang.t("supply_curve"));
+                    ui.strong(lang.t("demand_curve"));
+                    ui.strong(lang.t("demand_minus_supply"));
+                    ui.end_row();
+                    for quantity in self.model.sampled_quantities(self.x_max, self.curve_samples) {
+                        let supply = self.model.supply_formula(quantity);
+                        let demand = self.model.demand_formula(quantity);
+                        ui.label(lang.fmt_quantity(unit, quantity));
+                        ui.label(lang.fmt_money(currency, supply));
+                        ui.label(lang.fmt_money(currency, demand));
+                        ui.label(lang.fmt_signed_money(currency, demand - supply));
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+    }
+
+    // The price and quantity plot with its shaded areas, which can be
+    // clicked to set the control and dragged by its handles.
+    fn show_price_quantity_plot(
+        &mut self,
+        ui: &mut egui::Ui,
+        palette: &Palette,
+        legend: Option<Legend>,
+        height: Option<f32>,
+    ) {
+        let lang = self.lang();
+        let valid = self.model.is_valid();
+        let (currency, unit) = (self.currency.clone(), self.unit.clone());
+        let (currency, unit) = (currency.as_str(), unit.as_str());
+        let result = self.model.calculate_surplus();
+        let quantity_demanded = self.model.demand_quantity(result.price);
+        let quantity_supplied = result.quantity;
+        let shortage = (quantity_demanded - quantity_supplied).max(0.0);
+        let mut plot = with_legend(Plot::new("price_quantity_graph"), legend.clone());
+        if std::mem::take(&mut self.reset_plot) {
+            plot = plot.reset();
+            self.user_zoomed = false;
+        }
+        plot = plot
+            .x_axis_label(axis_label(lang.t("quantity"), unit))
+            .y_axis_label(axis_label(lang.t("price"), currency))
+            .x_axis_formatter(move |mark, _range| lang.fmt_num(mark.value))
+            .y_axis_formatter(move |mark, _range| lang.fmt_money(currency, mark.value))
+            .show_grid(self.show_grid);
+        plot = plot.include_x(0.0).include_x(self.x_max).include_y(0.0).include_y(self.y_max);
+        plot = plot.allow_drag(self.hovered_handle.is_none() && self.dragged_handle.is_none());
+        if let Some(height) = height {
+            plot = plot.height(height);
+        }
+        // Besides the curves the view has to take in the equilibrium and
+        // the line the control draws.
+        let mut landmarks: Vec<[f64; 2]> =
+            self.model.find_equilibrium().map(|(quantity, price)| [quantity, price]).into_iter().collect();
+        match self.model.price_control {
+            PriceControl::Ceiling | PriceControl::Floor => landmarks.push([0.0, self.model.price_limit]),
+            PriceControl::Quota => landmarks.push([self.model.quota, 0.0]),
+            PriceControl::None => {}
+        }
+        if !self.user_zoomed {
+            for [quantity, price] in &landmarks {
+                plot = plot.include_x(VIEW_MARGIN * quantity).include_y(VIEW_MARGIN * price);
+            }
+        }
+        let fit_view = std::mem::take(&mut self.fit_view);
+        self.user_zoomed |= fit_view;
+        let (supply, demand, demand_groups, fitted_bounds) = {
+            let sampled = self.sampled_curves();
+            // With nothing finite to fit there is no box, and the view stays.
+            let fitted_bounds = fit_view
+                .then(|| bounding_box(sampled.supply.iter().chain(&sampled.demand).chain(&landmarks).copied()))
+                .filter(PlotBounds::is_valid);
+            let (supply, demand) = (finite_points(sampled.supply.clone()), finite_points(sampled.demand.clone()));
+            (supply, demand, sampled.demand_groups.clone(), fitted_bounds)
+        };
+        let model = &self.model;
+        let show_price_quantities = self.show_price_quantities;
+        plot = plot.label_formatter(move |name, value| {
+            let mut label = if name.is_empty() { String::new() } else { format!("{}\n", name) };
+            let (demand_slope, supply_slope) = model.slopes(value.x);
+            label.push_str(&format!(
+                "{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}\n{}: {}",
+                lang.t("quantity"),
+                lang.fmt_quantity(unit, value.x),
+                lang.t("price"),
+                lang.fmt_money(currency, value.y),
+                lang.t("supply_curve"),
+                lang.fmt_money(currency, model.supply_formula(value.x)),
+                lang.t("demand_curve"),
+                lang.fmt_money(currency, model.demand_formula(value.x)),
+                lang.t("supply_slope_here"),
+                lang.fmt_num(supply_slope),
+                lang.t("demand_slope_here"),
+                lang.fmt_num(demand_slope)
+            ));
+            if show_price_quantities {
+                label.push_str(&format!(
+                    "\n{}: {}\n{}: {}",
+                    lang.t("quantity_supplied"),
+                    lang.fmt_quantity(unit, model.supply_quantity(value.y)),
+                    lang.t("quantity_demanded"),
+                    lang.fmt_quantity(unit, model.demand_quantity(value.y))
+                ));
+            }
+            label
+        });
+        let interaction = plot.show(ui, |plot_ui| {
+            if let Some(bounds) = fitted_bounds {
+                plot_ui.set_plot_bounds(bounds);
+            }
+            if let Some(snapshot) = &self.snapshot {
+                let faded = egui::Color32::from_gray(150).gamma_multiply(0.6);
+                let names = (lang.t("snapshot_supply").to_owned(), lang.t("snapshot_demand").to_owned());
+                overlay_market(plot_ui, snapshot, (self.x_max, self.curve_samples), names, faded);
+            }
+            for (i, entry) in self.gallery.iter().enumerate().filter(|(_, entry)| entry.shown) {
+                let color = GALLERY_COLORS[i % GALLERY_COLORS.len()].gamma_multiply(0.8);
+                let names = (
+                    format!("{} ({})", lang.t("supply_curve"), entry.name),
+                    format!("{} ({})", lang.t("demand_curve"), entry.name),
+                );
+                overlay_market(plot_ui, &entry.market, (self.x_max, self.curve_samples), names, color);
+            }
+
+            let price_limit_line: PlotPoints = vec![
+                [0.0, self.model.price_limit],
+                [self.x_max, self.model.price_limit],
+            ]
+                .into();
+
+            let outlines = self.model.surplus_outlines(&result);
+            let consumer_surplus_area = Polygon::new(finite_points(outlines.consumer_surplus.clone()))
+                .fill_color(palette.consumer_surplus.gamma_multiply(self.area_opacity));
+            let producer_surplus_area = Polygon::new(finite_points(outlines.producer_surplus.clone()))
+                .fill_color(palette.producer_surplus.gamma_multiply(self.area_opacity));
+
+            let wedge_area = Polygon::new(finite_points(vec![
+                [0.0, result.seller_price],
+                [result.quantity, result.seller_price],
+                [result.quantity, result.price],
+                [0.0, result.price],
+            ]));
+
+            let deadweight_loss_area = Polygon::new(finite_points(outlines.deadweight_loss))
+                .fill_color(palette.deadweight_loss);
+
+            // Without a crossing the areas and points would be drawn from
+            // clamped numbers, so only the curves are shown. Without trade
+            // the surplus areas and the wedge are only lines, so they give way
+            // to a label and just the deadweight loss is shaded.
+            let no_trade = result.no_trade();
+            if valid && no_trade {
+                plot_ui.text(
+                    Text::new(PlotPoint::new(self.x_max / 2.0, self.y_max * 0.95), lang.t("no_trade"))
+                        .color(egui::Color32::DARK_RED)
+                        .anchor(egui::Align2::CENTER_TOP),
+                );
+            }
+            if valid {
+                if self.show_consumer_surplus && !no_trade {
+                    plot_ui.polygon(consumer_surplus_area);
+                    if let Some(centroid) = centroid(&outlines.consumer_surplus) {
+                        plot_ui.text(Text::new(centroid, lang.fmt_money(currency, result.consumer_surplus)));
+                    }
+                }
+                if self.show_producer_surplus && !no_trade {
+                    plot_ui.polygon(producer_surplus_area);
+                    if let Some(centroid) = centroid(&outlines.producer_surplus) {
+                        plot_ui.text(Text::new(centroid, lang.fmt_money(currency, result.producer_surplus)));
+                    }
+                }
+                if self.show_deadweight_loss {
+                    if result.quantity > result.equilibrium_quantity {
+                        plot_ui.polygon(deadweight_loss_area.name(lang.t("overproduction")));
+                    } else {
+                        plot_ui.polygon(deadweight_loss_area);
+                    }
+                }
+                if no_trade {
+                    // Nothing traded, so no wedge to shade.
+                } else if result.price > result.seller_price {
+                    plot_ui.polygon(wedge_area.name(lang.t("tax_revenue")).fill_color(palette.tax_revenue));
+                } else if result.seller_price > result.price {
+                    plot_ui.polygon(
+                        wedge_area
+                            .name(lang.t("subsidy_cost"))
+                            .fill_color(palette.subsidy_cost),
+                    );
+                }
+                if shortage > 0.0 && shortage.is_finite() {
+                    let shortage_bracket: PlotPoints = vec![
+                        [quantity_supplied, result.price],
+                        [quantity_supplied, result.price - 0.5],
+                        [quantity_demanded, result.price - 0.5],
+                        [quantity_demanded, result.price],
+                    ]
+                        .into();
+                    plot_ui.line(
+                        Line::new(shortage_bracket)
+                            .name(lang.t("shortage"))
+                            .color(egui::Color32::DARK_RED),
+                    );
+                }
+                // Above a binding floor sellers offer more than buyers take;
+                // the gap is bracketed just over the floor.
+                if self.model.price_control == PriceControl::Floor {
+                    let offered = self.model.supply_quantity(result.seller_price);
+                    if offered > result.quantity && offered.is_finite() {
+                        let excess_bracket: PlotPoints = vec![
+                            [result.quantity, result.price],
+                            [result.quantity, result.price + 0.5],
+                            [offered, result.price + 0.5],
+                            [offered, result.price],
+                        ]
+                            .into();
+                        plot_ui.line(
+                            Line::new(excess_bracket)
+                                .name(lang.t("excess_supply_bracket"))
+                                .color(egui::Color32::DARK_BLUE),
+                        );
+                    }
+                }
+                // The base and heights that `0.5 * base * height` multiplies,
+                // bracketed just outside the axes. Off the equilibrium each
+                // area is a trapezoid, and the height is that of its side on
+                // the price axis.
+                if self.show_dimensions {
+                    let (dx, dy) = (0.02 * self.x_max, 0.02 * self.y_max);
+                    let base: PlotPoints =
+                        vec![[0.0, 0.0], [0.0, -dy], [result.quantity, -dy], [result.quantity, 0.0]].into();
+                    plot_ui.line(Line::new(base).color(egui::Color32::GRAY).width(1.0));
+                    plot_ui.text(
+                        Text::new(
+                            PlotPoint::new(0.5 * result.quantity, -dy),
+                            format!("q = {}", lang.fmt_quantity(unit, result.quantity)),
+                        )
+                        .anchor(egui::Align2::CENTER_TOP),
+                    );
+                    let mut height = |low: f64, high: f64, color: egui::Color32| {
+                        let bracket: PlotPoints = vec![[0.0, low], [-dx, low], [-dx, high], [0.0, high]].into();
+                        plot_ui.line(Line::new(bracket).color(color).width(1.0));
+                        plot_ui.text(
+                            Text::new(
+                                PlotPoint::new(-dx, 0.5 * (low + high)),
+                                format!("Δp = {}", lang.fmt_money(currency, high - low)),
+                            )
+                            .anchor(egui::Align2::RIGHT_CENTER)
+                            .color(color),
+                        );
+                    };
+                    if self.show_consumer_surplus {
+                        height(result.price, self.model.demand_formula(0.0), palette.consumer_surplus);
+                    }
+                    if self.show_producer_surplus {
+                        height(self.model.supply_formula(0.0), result.seller_price, palette.producer_surplus);
+                    }
+                }
+            }
+            if self.show_supply {
+                plot_ui.line(
+                    Line::new(supply)
+                        .name(lang.t("supply_curve"))
+                        .width(self.supply_look.width)
+                        .style(self.supply_look.style(LineStyle::Solid)),
+                );
+            }
+            if self.show_demand {
+                plot_ui.line(
+                    Line::new(demand)
+                        .name(lang.t("demand_curve"))
+                        .width(self.demand_look.width)
+                        .style(self.demand_look.style(palette.demand_style)),
+                );
+                // Dashed, so the groups stand apart from the market demand
+                // they add up to.
+                for (key, points) in ["first_group_demand", "second_group_demand"]
+                    .into_iter()
+                    .zip(demand_groups.into_iter().flatten())
+                {
+                    let line = Line::new(finite_points(points)).name(lang.t(key));
+                    plot_ui.line(line.style(LineStyle::dashed_loose()));
+                }
+            }
+            // A limit that changes nothing is drawn faded; a transparent
+            // colour lets the plot pick one as usual.
+            let (limit_color, limit_style) = if result.binding {
+                (egui::Color32::TRANSPARENT, self.limit_look.style(palette.price_limit_style))
+            } else {
+                (egui::Color32::GRAY, LineStyle::dashed_dense())
+            };
+            match self.model.price_control {
+                _ if !self.show_price_limit => {}
+                PriceControl::Ceiling => plot_ui.line(
+                    Line::new(price_limit_line)
+                        .name(lang.t("price_ceiling"))
+                        .color(limit_color)
+                        .width(self.limit_look.width)
+                        .style(limit_style),
+                ),
+                PriceControl::Floor => plot_ui.line(
+                    Line::new(price_limit_line)
+                        .name(lang.t("price_floor"))
+                        .color(limit_color)
+                        .width(self.limit_look.width)
+                        .style(limit_style),
+                ),
+                PriceControl::Quota => plot_ui.vline(
+                    VLine::new(self.model.quota)
+                        .name(lang.t("quota"))
+                        .color(limit_color)
+                        .width(self.limit_look.width)
+                        .style(limit_style),
+                ),
+                PriceControl::None => {}
+            }
+
+            if self.show_trapezoids && valid && !result.no_trade() {
+                let (consumer, producer) = self.model.surplus_trapezoids(self.trapezoid_steps);
+                let outlined = [(consumer, palette.consumer_surplus), (producer, palette.producer_surplus)];
+                for (trapezoids, color) in outlined {
+                    for trapezoid in trapezoids {
+                        plot_ui.polygon(
+                            Polygon::new(finite_points(trapezoid.to_vec()))
+                                .fill_color(egui::Color32::TRANSPARENT)
+                                .stroke(egui::Stroke::new(1.0, color)),
+                        );
+                    }
+                }
+            }
+            if let Some(fit) = self.observation_fit {
+                // One standard error either side of the fitted line, to
+                // show how loosely the data pin it down.
+                let error = fit.standard_error;
+                let edge = |quantity: f64, side: f64| {
+                    [quantity, fit.slope * quantity + fit.intercept + side * error]
+                };
+                let x_max = self.x_max;
+                let band = vec![edge(0.0, 1.0), edge(x_max, 1.0), edge(x_max, -1.0), edge(0.0, -1.0)];
+                plot_ui.polygon(
+                    Polygon::new(finite_points(band))
+                        .name(lang.t("confidence_band"))
+                        .fill_color(egui::Color32::DARK_GRAY.gamma_multiply(0.15))
+                        .stroke(egui::Stroke::NONE),
+                );
+            }
+            for &marker in &self.markers {
+                plot_ui.points(
+                    Points::new(vec![marker]).name(lang.t("marker")).shape(MarkerShape::Cross).radius(5.0),
+                );
+                let label =
+                    format!("({}, {})", lang.fmt_quantity(unit, marker[0]), lang.fmt_money(currency, marker[1]));
+                plot_ui.text(Text::new(marker.into(), label).anchor(egui::Align2::LEFT_BOTTOM));
+            }
+            if !self.observations.is_empty() {
+                plot_ui.points(
+                    Points::new(self.observations.clone())
+                        .name(lang.t("observations"))
+                        .radius(3.0)
+                        .color(egui::Color32::DARK_GRAY),
+                );
+            }
+            if self.show_demand {
+                // Where demand meets the axes: what the keenest buyer would
+                // pay, and how much buyers would take if it were free.
+                let marker_color = egui::Color32::from_rgb(90, 90, 160);
+                let choke_price = [0.0, self.model.demand_formula(0.0)];
+                plot_ui.points(
+                    Points::new(finite_points(vec![choke_price]))
+                        .name(lang.t("choke_price"))
+                        .radius(4.0)
+                        .color(marker_color),
+                );
+                plot_ui.text(
+                    Text::new(choke_price.into(), lang.t("choke_price"))
+                        .anchor(egui::Align2::LEFT_BOTTOM)
+                        .color(marker_color),
+                );
+                let max_quantity = self.model.demand_quantity(0.0);
+                if max_quantity.is_finite() {
+                    let max_quantity = [max_quantity, 0.0];
+                    plot_ui.points(
+                        Points::new(vec![max_quantity])
+                            .name(lang.t("max_quantity"))
+                            .radius(4.0)
+                            .color(marker_color),
+                    );
+                    plot_ui.text(
+                        Text::new(max_quantity.into(), lang.t("max_quantity"))
+                            .anchor(egui::Align2::LEFT_BOTTOM)
+                            .color(marker_color),
+                    );
+                }
+            }
+            if valid && self.show_guides {
+                // The textbook's dotted lines from the equilibrium down to
+                // either axis, with the values read off where they land.
+                let (quantity, price) = (result.equilibrium_quantity, result.equilibrium_price);
+                for guide in [[[quantity, price], [quantity, 0.0]], [[quantity, price], [0.0, price]]] {
+                    plot_ui.line(
+                        Line::new(finite_points(guide.to_vec()))
+                            .color(egui::Color32::GRAY)
+                            .width(1.0)
+                            .style(LineStyle::dotted_dense()),
+                    );
+                }
+                plot_ui.text(
+                    Text::new(PlotPoint::new(quantity, 0.0), format!("Q* = {}", lang.fmt_quantity(unit, quantity)))
+                        .anchor(egui::Align2::LEFT_BOTTOM),
+                );
+                plot_ui.text(
+                    Text::new(PlotPoint::new(0.0, price), format!("P* = {}", lang.fmt_money(currency, price)))
+                        .anchor(egui::Align2::LEFT_BOTTOM),
+                );
+            }
+            if valid {
+                plot_ui.points(
+                    Points::new(finite_points(vec![[result.equilibrium_quantity, result.equilibrium_price]]))
+                        .name(lang.t("equilibrium"))
+                        .radius(5.0)
+                        .color(egui::Color32::from_rgb(0, 150, 0)),
+                );
+                if result.quantity != result.equilibrium_quantity || result.price != result.equilibrium_price {
+                    plot_ui.points(
+                        Points::new(finite_points(vec![[result.quantity, result.price]]))
+                            .name(lang.t("traded_point"))
+                            .radius(5.0)
+                            .color(egui::Color32::from_rgb(200, 0, 200)),
+                    );
+                }
+            }
+            // A ring widening from the equilibrium and a check mark, both
+            // fading, just after the market stopped being rationed.
+            if let Some(elapsed) = self.clears_flash.filter(|_| valid) {
+                let t = (elapsed / CLEARS_FLASH_SECONDS) as f32;
+                let color = egui::Color32::from_rgb(0, 150, 0).gamma_multiply(1.0 - t);
+                let equilibrium = [result.equilibrium_quantity, result.equilibrium_price];
+                plot_ui.points(Points::new(vec![equilibrium]).radius(5.0 + 25.0 * t).filled(false).color(color));
+                plot_ui.text(
+                    Text::new(equilibrium.into(), egui::RichText::new(lang.t("market_clears")).strong())
+                        .anchor(egui::Align2::LEFT_BOTTOM)
+                        .color(color),
+                );
+            }
+            // Where each intervention of the same severity would have the
+            // market trade, at the price buyers pay.
+            let severity = self.intervention_severity;
+            let matched = self.show_interventions.then(|| self.model.matched_interventions(severity));
+            if let Some(matched) = matched.flatten().filter(|_| valid) {
+                for (key, outcome, shape) in [
+                    ("ceiling", &matched.ceiling, MarkerShape::Down),
+                    ("floor", &matched.floor, MarkerShape::Up),
+                    ("tax", &matched.tax, MarkerShape::Square),
+                    ("quota", &matched.quota, MarkerShape::Diamond),
+                ] {
+                    plot_ui.points(
+                        Points::new(finite_points(vec![[outcome.quantity, outcome.price]]))
+                            .name(lang.t(key))
+                            .shape(shape)
+                            .filled(true)
+                            .radius(6.0),
+                    );
+                }
+            }
+
+            let pointer = plot_ui.pointer_coordinate();
+            let near_handle = pointer.and_then(|pointer| {
+                let screen_pointer = plot_ui.screen_from_plot(pointer);
+                let near_point = |price: f64| {
+                    plot_ui.screen_from_plot(PlotPoint::new(0.0, price)).distance(screen_pointer) < 8.0
+                };
+                let near_line = |price: f64| {
+                    (plot_ui.screen_from_plot(PlotPoint::new(pointer.x, price)).y - screen_pointer.y).abs() < 6.0
+                };
+                // The intercepts sit on the price axis, where the limit line
+                // also runs, so they are picked first.
+                let supply_draggable = self.show_supply && self.model.supply_model != SupplyModel::Custom;
+                let demand_draggable = self.show_demand && self.model.demand_model == DemandModel::Linear;
+                let limit_draggable = self.show_price_limit
+                    && matches!(self.model.price_control, PriceControl::Ceiling | PriceControl::Floor);
+                if supply_draggable && near_point(self.model.supply_formula(0.0)) {
+                    Some(DragHandle::SupplyIntercept)
+                } else if demand_draggable && near_point(self.model.demand_formula(0.0)) {
+                    Some(DragHandle::DemandIntercept)
+                } else if limit_draggable && near_line(self.model.price_limit) {
+                    Some(DragHandle::PriceLimit)
+                } else if demand_draggable
+                    && self.model.find_equilibrium().is_some()
+                    && near_line(self.model.demand_formula(pointer.x))
+                {
+                    Some(DragHandle::DemandPivot)
+                } else {
+                    None
+                }
+            });
+            PlotInteraction {
+                clicked: if plot_ui.response().clicked() { pointer } else { None },
+                right_clicked: if plot_ui.response().secondary_clicked() { pointer } else { None },
+                pointer,
+                near_handle,
+                bounds: plot_ui.plot_bounds(),
+            }
+        });
+
+        let response = &interaction.response;
+        // A screen reader cannot see the plot, so it gets the points that
+        // matter in words.
+        let summary = format!(
+            "{}. {}: {}, {}. {}: {}, {}.",
+            lang.t("plot_summary"),
+            lang.t("equilibrium"),
+            lang.fmt_money(currency, result.equilibrium_price),
+            lang.fmt_quantity(unit, result.equilibrium_quantity),
+            lang.t("traded_point"),
+            lang.fmt_money(currency, result.price),
+            lang.fmt_quantity(unit, result.quantity)
+        );
+        response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Other, true, &summary));
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.plot_rect = Some(response.rect);
+        }
+        // Dragging anything but a handle pans the view or zooms it to a box,
+        // and scrolling over it pans or zooms too; double-clicking gives the
+        // view back.
+        let scrolled = response.hovered()
+            && ui.input(|input| input.smooth_scroll_delta != egui::Vec2::ZERO || input.zoom_delta() != 1.0);
+        let on_handle = self.dragged_handle.is_some() || interaction.inner.near_handle.is_some();
+        let panned = response.dragged() && !on_handle;
+        if panned || scrolled {
+            self.user_zoomed = true;
+        }
+        if response.double_clicked() {
+            self.user_zoomed = false;
+        }
+        let interaction = interaction.inner;
+        if let Some(clicked) = interaction.clicked {
+            if self.model.price_control == PriceControl::Quota {
+                if !self.locks.holds("quota") {
+                    self.model.quota = clicked.x.clamp(0.0, self.x_max);
+                }
+            } else if !self.locks.holds("price_limit") {
+                self.model.price_limit = clicked.y.clamp(0.0, self.y_max);
+                self.snap_price_limit();
+            }
+        }
+        if interaction.right_clicked.is_some() {
+            self.context_point = interaction.right_clicked;
+        }
+        response.context_menu(|ui| {
+            let Some(point) = self.context_point else { return };
+            if ui.button(lang.t("set_ceiling_here")).clicked() {
+                self.model.price_control = PriceControl::Ceiling;
+                self.model.price_limit = point.y.clamp(0.0, self.y_max);
+                self.snap_price_limit();
+                ui.close_menu();
+            }
+            if ui.button(lang.t("add_marker")).clicked() {
+                self.markers.push([point.x, point.y]);
+                ui.close_menu();
+            }
+            if !self.markers.is_empty() && ui.button(lang.t("clear_markers")).clicked() {
+                self.markers.clear();
+                ui.close_menu();
+            }
+            if ui.button(lang.t("reset_zoom")).clicked() {
+                self.reset_plot = true;
+                ui.close_menu();
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            if ui.button(lang.t("export_csv")).clicked() {
+                self.status = match self.export_csv(&self.export_path) {
+                    Ok(()) => format!("{} {}", lang.t("exported_to"), self.export_path),
+                    Err(err) => format!("{}: {}", lang.t("export_failed"), err),
+                };
+                ui.close_menu();
+            }
+        });
+        self.hovered_handle = interaction.near_handle;
+        if response.drag_started() && interaction.near_handle.is_some() {
+            self.dragged_handle = interaction.near_handle.filter(|&handle| !self.locks.hold_handle(handle));
+        }
+        if let Some(handle) = self.dragged_handle {
+            if let Some(pointer) = interaction.pointer {
+                let (bottom, top) = (interaction.bounds.min()[1], interaction.bounds.max()[1]);
+                let price = pointer.y.max(bottom).min(top);
+                // The intercepts move the curve's own constant term, so
+                // that with the shift added the curve meets the axis at
+                // the pointer.
+                let model = &mut self.model;
+                let (supply_offset, demand_offset) = (model.supply_transform().0, model.demand_transform().0);
+                match handle {
+                    DragHandle::PriceLimit => {
+                        model.price_limit = price.clamp(0.0, self.y_max);
+                        self.snap_price_limit();
+                    }
+                    DragHandle::SupplyIntercept => match &mut model.supply_model {
+                        SupplyModel::Linear => {
+                            model.supply_intercept = (price - supply_offset).clamp(0.0, 15.0);
+                        }
+                        SupplyModel::Quadratic { c, .. } => *c = (price - supply_offset).clamp(0.0, 15.0),
+                        SupplyModel::Custom => {}
+                    },
+                    DragHandle::DemandIntercept => {
+                        model.demand_intercept = (price - demand_offset).clamp(0.0, 30.0);
+                    }
+                    // The line through the equilibrium and the pointer,
+                    // within the slope slider's range, until the pointer
+                    // comes too close to the pivot to give a direction.
+                    DragHandle::DemandPivot => {
+                        if let Some((quantity, equilibrium_price)) = model.find_equilibrium() {
+                            if (pointer.x - quantity).abs() > 0.01 * self.x_max {
+                                let stretch = model.demand_transform().1;
+                                let slope = (price - equilibrium_price) / (pointer.x - quantity) / stretch;
+                                model.demand_slope = slope.clamp(-5.0, -0.1);
+                                model.demand_intercept =
+                                    equilibrium_price - model.demand_slope * stretch * quantity - demand_offset;
+                            }
+                        }
+                    }
+                }
+            }
+            if response.drag_stopped() {
+                self.dragged_handle = None;
+            }
+        }
+        match self.dragged_handle.or(self.hovered_handle) {
+            Some(DragHandle::DemandPivot) => ui.ctx().set_cursor_icon(egui::CursorIcon::Grab),
+            Some(_) => ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeVertical),
+            None => {}
+        }
+    }
+
+    // The curves with both axes on a log scale, where constant-elasticity
+    // demand is a straight line whose slope is minus one over the elasticity.
+    // The areas are left out, as they no longer measure surplus.
+    fn show_log_plot(&mut self, ui: &mut egui::Ui, legend: Option<Legend>, height: Option<f32>) {
+        // Three decades below the widest quantity shown, sampled evenly in
+        // log space so the line does not bunch up at the right.
+        const DECADES: f64 = 3.0;
+        const SAMPLES: usize = 200;
+        let lang = self.lang();
+        let (currency, unit) = (self.currency.as_str(), self.unit.as_str());
+        let to_log = |[quantity, price]: [f64; 2]| {
+            (quantity > 0.0 && price > 0.0).then(|| [quantity.log10(), price.log10()])
+        };
+        let curve_points = |curve: &dyn Fn(f64) -> f64| -> Vec<[f64; 2]> {
+            (0..=SAMPLES)
+                .map(|i| self.x_max.log10() - DECADES * (1.0 - i as f64 / SAMPLES as f64))
+                .filter_map(|log_quantity| {
+                    let quantity = 10f64.powf(log_quantity);
+                    to_log([quantity, curve(quantity)])
+                })
+                .collect()
+        };
+        let supply = curve_points(&|quantity| self.model.supply_formula(quantity));
+        let demand = curve_points(&|quantity| self.model.demand_formula(quantity));
+
+        let mut plot = with_legend(Plot::new("log_price_quantity_graph"), legend)
+            .x_axis_label(format!("{} · {}", axis_label(lang.t("quantity"), unit), lang.t("log_scale")))
+            .y_axis_label(format!("{} · {}", axis_label(lang.t("price"), currency), lang.t("log_scale")))
+            .x_axis_formatter(move |mark, _range| lang.fmt_num(10f64.powf(mark.value)))
+            .y_axis_formatter(move |mark, _range| lang.fmt_money(currency, 10f64.powf(mark.value)))
+            .label_formatter(move |name, value| {
+                let mut label = if name.is_empty() { String::new() } else { format!("{}\n", name) };
+                label.push_str(&format!(
+                    "{}: {}\n{}: {}",
+                    lang.t("quantity"),
+                    lang.fmt_quantity(unit, 10f64.powf(value.x)),
+                    lang.t("price"),
+                    lang.fmt_money(currency, 10f64.powf(value.y))
+                ));
+                label
+            })
+            .show_grid(self.show_grid);
+        if let Some(height) = height {
+            plot = plot.height(height);
+        }
+        #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+        let response = plot.show(ui, |plot_ui| {
+            if self.show_supply {
+                plot_ui.line(
+                    Line::new(finite_points(supply))
+                        .name(lang.t("supply_curve"))
+                        .width(self.supply_look.width)
+                        .style(self.supply_look.style(LineStyle::Solid)),
+                );
+            }
+            if self.show_demand {
+                plot_ui.line(
+                    Line::new(finite_points(demand))
+                        .name(lang.t("demand_curve"))
+                        .width(self.demand_look.width)
+                        .style(self.demand_look.style(LineStyle::Solid)),
+                );
+            }
+            // A limit at zero has no place on a log scale.
+            match self.model.price_control {
+                _ if !self.show_price_limit => {}
+                PriceControl::Ceiling | PriceControl::Floor if self.model.price_limit > 0.0 => {
+                    plot_ui.hline(HLine::new(self.model.price_limit.log10()).name(lang.t("price_limit")))
+                }
+                PriceControl::Quota if self.model.quota > 0.0 => {
+                    plot_ui.vline(VLine::new(self.model.quota.log10()).name(lang.t("quota")))
+                }
+                _ => {}
+            }
+            if let Some(equilibrium) = self.model.find_equilibrium().and_then(|(q, p)| to_log([q, p])) {
+                plot_ui.points(
+                    Points::new(vec![equilibrium])
+                        .name(lang.t("equilibrium"))
+                        .radius(5.0)
+                        .color(egui::Color32::from_rgb(0, 150, 0)),
+                );
+            }
+        });
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.plot_rect = Some(response.response.rect);
+        }
+    }
+
+    // The curves, areas, control and points of the main plot with the axes
+    // swapped, quantity up and price across. Only where each `[q, p]` lands
+    // changes; the handles and click to set stay with the usual plot.
+    fn show_transposed_plot(
+        &mut self,
+        ui: &mut egui::Ui,
+        palette: &Palette,
+        legend: Option<Legend>,
+        height: Option<f32>,
+    ) {
+        let lang = self.lang();
+        let valid = self.model.is_valid();
+        let (currency, unit) = (self.currency.clone(), self.unit.clone());
+        let (currency, unit) = (currency.as_str(), unit.as_str());
+        let result = self.model.calculate_surplus();
+        let flip = |points: &[[f64; 2]]| {
+            finite_points(points.iter().map(|&[quantity, price]| [price, quantity]).collect())
+        };
+        let (supply, demand, demand_groups) = {
+            let sampled = self.sampled_curves();
+            let demand_groups =
+                sampled.demand_groups.as_ref().map(|groups| groups.each_ref().map(|group| flip(group)));
+            (flip(&sampled.supply), flip(&sampled.demand), demand_groups)
+        };
+        let mut plot = with_legend(Plot::new("transposed_price_quantity_graph"), legend)
+            .x_axis_label(axis_label(lang.t("price"), currency))
+            .y_axis_label(axis_label(lang.t("quantity"), unit))
+            .x_axis_formatter(move |mark, _range| lang.fmt_money(currency, mark.value))
+            .y_axis_formatter(move |mark, _range| lang.fmt_num(mark.value))
+            .label_formatter(move |name, value| {
+                let mut label = if name.is_empty() { String::new() } else { format!("{}\n", name) };
+                label.push_str(&format!(
+                    "{}: {}\n{}: {}",
+                    lang.t("price"),
+                    lang.fmt_money(currency, value.x),
+                    lang.t("quantity"),
+                    lang.fmt_quantity(unit, value.y)
+                ));
+                label
+            })
+            .include_x(0.0)
+            .include_x(self.y_max)
+            .include_y(0.0)
+            .include_y(self.x_max)
+            .show_grid(self.show_grid);
+        if let Some(height) = height {
+            plot = plot.height(height);
+        }
+        #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+        let response = plot.show(ui, |plot_ui| {
+            if valid {
+                let outlines = self.model.surplus_outlines(&result);
+                for (shown, outline, color) in [
+                    (self.show_consumer_surplus, &outlines.consumer_surplus, palette.consumer_surplus),
+                    (self.show_producer_surplus, &outlines.producer_surplus, palette.producer_surplus),
+                ] {
+                    if shown && !result.no_trade() {
+                        let area = Polygon::new(flip(outline)).fill_color(color.gamma_multiply(self.area_opacity));
+                        plot_ui.polygon(area);
+                    }
+                }
+                if self.show_deadweight_loss {
+                    let area = Polygon::new(flip(&outlines.deadweight_loss)).fill_color(palette.deadweight_loss);
+                    plot_ui.polygon(area);
+                }
+            }
+            if self.show_supply {
+                plot_ui.line(
+                    Line::new(supply)
+                        .name(lang.t("supply_curve"))
+                        .width(self.supply_look.width)
+                        .style(self.supply_look.style(LineStyle::Solid)),
+                );
+            }
+            if self.show_demand {
+                plot_ui.line(
+                    Line::new(demand)
+                        .name(lang.t("demand_curve"))
+                        .width(self.demand_look.width)
+                        .style(self.demand_look.style(palette.demand_style)),
+                );
+                for (key, points) in ["first_group_demand", "second_group_demand"]
+                    .into_iter()
+                    .zip(demand_groups.into_iter().flatten())
+                {
+                    plot_ui.line(Line::new(points).name(lang.t(key)).style(LineStyle::dashed_loose()));
+                }
+            }
+            // A price limit is now a vertical line, and a quota a level one.
+            match self.model.price_control {
+                _ if !self.show_price_limit => {}
+                PriceControl::Ceiling | PriceControl::Floor => plot_ui.vline(
+                    VLine::new(self.model.price_limit)
+                        .name(lang.t("price_limit"))
+                        .width(self.limit_look.width)
+                        .style(self.limit_look.style(palette.price_limit_style)),
+                ),
+                PriceControl::Quota => plot_ui.hline(HLine::new(self.model.quota).name(lang.t("quota"))),
+                PriceControl::None => {}
+            }
+            if valid {
+                plot_ui.points(
+                    Points::new(flip(&[[result.equilibrium_quantity, result.equilibrium_price]]))
+                        .name(lang.t("equilibrium"))
+                        .radius(5.0)
+                        .color(egui::Color32::from_rgb(0, 150, 0)),
+                );
+                if result.binding {
+                    plot_ui.points(
+                        Points::new(flip(&[[result.quantity, result.price]]))
+                            .name(lang.t("traded_point"))
+                            .radius(5.0)
+                            .color(egui::Color32::from_rgb(200, 0, 200)),
+                    );
+                }
+            }
+        });
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.plot_rect = Some(response.response.rect);
+        }
+    }
+
+    // The price and quantity plot, with any of the smaller plots below it.
+    fn show_plots(&mut self, ui: &mut egui::Ui, palette: &Palette) {
+        let lang = self.lang();
+        let (currency, unit) = (self.currency.clone(), self.unit.clone());
+        let (currency, unit) = (currency.as_str(), unit.as_str());
+        let substitute = PriceLimitSimulator {
+            demand_shift: self.substitute.demand_shift + self.model.spillover(self.cross_price),
+            ..self.substitute.clone()
+        };
+
+        let legend = self.show_legend.then(|| Legend::default().position(self.legend_corner));
+        // The main plot shares the height evenly with any plots below it.
+        let mut lower_plots = [
+            self.show_welfare_sweep,
+            self.show_tax_sweep,
+            self.show_breakdown,
+            self.show_monte_carlo,
+            self.show_substitute,
+        ]
+        .into_iter()
+        .filter(|shown| *shown)
+        .count();
+        let height = (lower_plots > 0).then(|| ui.available_height() / (lower_plots + 1) as f32);
+        if self.log_axes {
+            self.show_log_plot(ui, legend.clone(), height);
+        } else if self.transposed {
+            self.show_transposed_plot(ui, palette, legend.clone(), height);
+        } else {
+            self.show_price_quantity_plot(ui, palette, legend.clone(), height);
+        }
+
+        if self.show_welfare_sweep {
+            let price_limit = self.model.price_limit;
+            let market = PriceLimitSimulator { price_limit: 0.0, ..self.model.clone() };
+            let best_ceiling = self
+                .best_ceiling
+                .as_ref()
+                .filter(|best| best.market == market)
+                .map(|best| [best.price_limit, best.consumer_surplus]);
+            let height = ui.available_height() / lower_plots as f32;
+            lower_plots -= 1;
+            let weight = self.show_weighted_welfare.then_some(self.welfare_weight);
+            let sweep = self.welfare_sweep();
+            with_legend(Plot::new("welfare_sweep"), legend.clone())
+                .height(height)
+                .x_axis_label(axis_label(lang.t("price_limit"), currency))
+                .y_axis_label(axis_label(lang.t("money_amount"), currency))
+                .x_axis_formatter(move |mark, _range| lang.fmt_money(currency, mark.value))
+                .y_axis_formatter(move |mark, _range| lang.fmt_money(currency, mark.value))
+                .show(ui, |plot_ui| {
+                    plot_ui.line(Line::new(finite_points(sweep.consumer_surplus.clone()))
+                        .name(lang.t("consumer_surplus")));
+                    plot_ui.line(Line::new(finite_points(sweep.producer_surplus.clone()))
+                        .name(lang.t("producer_surplus")));
+                    plot_ui.line(Line::new(finite_points(sweep.deadweight_loss.clone()))
+                        .name(lang.t("deadweight_loss")));
+                    if let Some(weight) = weight {
+                        let weighted = sweep
+                            .consumer_surplus
+                            .iter()
+                            .zip(&sweep.producer_surplus)
+                            .map(|(&[price_limit, consumer], &[_, producer])| {
+                                [price_limit, weight * consumer + (1.0 - weight) * producer]
+                            })
+                            .collect();
+                        plot_ui.line(Line::new(finite_points(weighted)).name(lang.t("weighted_welfare")));
+                    }
+                    plot_ui.vline(VLine::new(price_limit).name(lang.t("price_limit")));
+                    if let Some(best) = best_ceiling {
+                        plot_ui.points(Points::new(vec![best]).name(lang.t("best_ceiling")).radius(5.0));
+                    }
+                });
+        }
+        if self.show_tax_sweep {
+            let tax_per_unit = self.model.tax();
+            let height = ui.available_height() / lower_plots as f32;
+            lower_plots -= 1;
+            let sweep = self.tax_sweep();
+            with_legend(Plot::new("tax_sweep"), legend.clone())
+                .height(height)
+                .x_axis_label(axis_label(lang.t("tax_per_unit"), currency))
+                .y_axis_label(axis_label(lang.t("money_amount"), currency))
+                .x_axis_formatter(move |mark, _range| lang.fmt_money(currency, mark.value))
+                .y_axis_formatter(move |mark, _range| lang.fmt_money(currency, mark.value))
+                .show(ui, |plot_ui| {
+                    plot_ui.line(Line::new(finite_points(sweep.tax_revenue.clone()))
+                        .name(lang.t("tax_revenue")));
+                    plot_ui.line(Line::new(finite_points(sweep.deadweight_loss.clone()))
+                        .name(lang.t("deadweight_loss")));
+                    plot_ui.points(
+                        Points::new(vec![sweep.peak_revenue])
+                            .name(lang.t("peak_revenue"))
+                            .radius(5.0),
+                    );
+                    let peak_label =
+                        format!("{} {}", lang.t("peak_revenue"), lang.fmt_money(currency, sweep.peak_revenue[0]));
+                    plot_ui.text(
+                        Text::new(sweep.peak_revenue.into(), peak_label).anchor(egui::Align2::CENTER_BOTTOM),
+                    );
+                    plot_ui.vline(VLine::new(tax_per_unit).name(lang.t("tax_per_unit")));
+                });
+        }
+        if self.show_breakdown {
+            let height = ui.available_height() / lower_plots as f32;
+            lower_plots -= 1;
+            let result = self.model.calculate_surplus();
+            let mut parts = vec![
+                ("consumer_surplus", result.consumer_surplus, palette.consumer_surplus),
+                ("producer_surplus", result.producer_surplus, palette.producer_surplus),
+            ];
+            if result.tax_revenue > 0.0 {
+                parts.push(("tax_revenue", result.tax_revenue, palette.tax_revenue));
+            }
+            parts.push(("deadweight_loss", result.deadweight_loss, palette.deadweight_loss));
+            let free_market_total = self.model.without_intervention().calculate_surplus().total_surplus();
+            let (scale, unit) = if self.surplus_as_share && free_market_total > f64::EPSILON {
+                (100.0 / free_market_total, "%")
+            } else {
+                (1.0, currency)
+            };
+            for (_, value, _) in &mut parts {
+                *value *= scale;
+            }
+            with_legend(Plot::new("surplus_breakdown"), legend.clone())
+                .height(height)
+                .show_axes([true, false])
+                .x_axis_label(axis_label(lang.t("surplus_breakdown"), unit))
+                .x_axis_formatter(move |mark, _range| match unit {
+                    "%" => format!("{}%", lang.fmt_num(mark.value)),
+                    _ => lang.fmt_money(unit, mark.value),
+                })
+                .include_x(0.0)
+                .show(ui, |plot_ui| {
+                    // Each part starts where the ones before it end.
+                    let mut stacked: Vec<BarChart> = Vec::new();
+                    for (key, value, color) in parts {
+                        let bar = Bar::new(0.0, value.max(0.0)).width(0.6).name(lang.t(key));
+                        let below: Vec<&BarChart> = stacked.iter().collect();
+                        let chart = BarChart::new(vec![bar]).horizontal().name(lang.t(key)).color(color);
+                        stacked.push(chart.stack_on(&below));
+                    }
+                    for chart in stacked {
+                        plot_ui.bar_chart(chart);
+                    }
+                });
+        }
+        if self.show_monte_carlo {
+            let height = ui.available_height() / lower_plots as f32;
+            let measure = self.monte_carlo_measure;
+            let as_set = measure.of(&self.model.calculate_surplus());
+            let values: Vec<f64> = self.monte_carlo().outcomes.iter().map(|outcome| measure.of(outcome)).collect();
+            let (counts, width) = histogram(&values);
+            let bars = counts.into_iter().map(|[value, count]| Bar::new(value, count).width(width)).collect();
+            with_legend(Plot::new("monte_carlo"), legend.clone())
+                .height(height)
+                .x_axis_label(axis_label(lang.t(measure.key()), currency))
+                .y_axis_label(lang.t("draws"))
+                .x_axis_formatter(move |mark, _range| lang.fmt_money(currency, mark.value))
+                .show(ui, |plot_ui| {
+                    plot_ui.bar_chart(BarChart::new(bars).name(lang.t(measure.key())));
+                    plot_ui.vline(VLine::new(as_set).name(lang.t("as_set")));
+                });
+        }
+        if self.show_substitute {
+            let (x_max, samples) = (self.x_max, self.curve_samples);
+            // Normalized, each market is measured against its own free
+            // market, so 1 is the price it would clear at unregulated.
+            let scales = match (
+                self.model.without_intervention().find_equilibrium(),
+                self.substitute.find_equilibrium(),
+            ) {
+                (Some((_, main_price)), Some((_, substitute_price))) if self.normalize_prices => {
+                    Some((main_price, substitute_price))
+                }
+                _ => None,
+            };
+            let substitute_scale = scales.map_or(1.0, |(_, substitute_price)| substitute_price);
+            let scaled = |points: Vec<[f64; 2]>, scale: f64| {
+                finite_points(points.into_iter().map(|[quantity, price]| [quantity, price / scale]).collect())
+            };
+            let (y_label, y_top) = match scales {
+                Some(_) => (lang.t("price_relative").to_owned(), 2.0),
+                None => (axis_label(lang.t("price"), currency), self.y_max),
+            };
+            let relative = scales.is_some();
+            with_legend(Plot::new("substitute_market"), legend)
+                .x_axis_label(axis_label(lang.t("quantity"), unit))
+                .y_axis_label(y_label)
+                .x_axis_formatter(move |mark, _range| lang.fmt_num(mark.value))
+                .y_axis_formatter(move |mark, _range| {
+                    if relative { lang.fmt_num(mark.value) } else { lang.fmt_money(currency, mark.value) }
+                })
+                .include_x(0.0)
+                .include_x(x_max)
+                .include_y(0.0)
+                .include_y(y_top)
+                .show(ui, |plot_ui| {
+                    if let Some((main_scale, _)) = scales {
+                        let faded = egui::Color32::from_gray(120).gamma_multiply(0.7);
+                        plot_ui.line(
+                            Line::new(scaled(self.model.supply_points(x_max, samples), main_scale))
+                                .name(lang.t("main_supply"))
+                                .color(faded),
+                        );
+                        plot_ui.line(
+                            Line::new(scaled(self.model.demand_points(x_max, samples), main_scale))
+                                .name(lang.t("main_demand"))
+                                .color(faded)
+                                .style(palette.demand_style),
+                        );
+                        if matches!(self.model.price_control, PriceControl::Ceiling | PriceControl::Floor) {
+                            let limit = self.model.price_limit / main_scale;
+                            plot_ui.line(
+                                Line::new(PlotPoints::new(vec![[0.0, limit], [x_max, limit]]))
+                                    .name(lang.t("price_limit"))
+                                    .color(faded)
+                                    .style(LineStyle::dashed_dense()),
+                            );
+                        }
+                    }
+                    plot_ui.line(
+                        Line::new(scaled(self.substitute.demand_points(x_max, samples), substitute_scale))
+                            .name(lang.t("demand_without_spillover"))
+                            .color(egui::Color32::from_gray(150).gamma_multiply(0.6))
+                            .style(LineStyle::dashed_loose()),
+                    );
+                    plot_ui.line(
+                        Line::new(scaled(substitute.supply_points(x_max, samples), substitute_scale))
+                            .name(lang.t("supply_curve")),
+                    );
+                    plot_ui.line(
+                        Line::new(scaled(substitute.demand_points(x_max, samples), substitute_scale))
+                            .name(lang.t("demand_curve"))
+                            .style(palette.demand_style),
+                    );
+                    if let Some((quantity, price)) = substitute.find_equilibrium() {
+                        plot_ui.points(
+                            Points::new(finite_points(vec![[quantity, price / substitute_scale]]))
+                                .name(lang.t("equilibrium"))
+                                .radius(5.0)
+                                .color(egui::Color32::from_rgb(0, 150, 0)),
+                        );
+                    }
+                });
+        }
+    }
+}
+
+// Points for a line, polygon or markers without any that are not finite.
+// One infinite or NaN price, such as a typed `10 / q` at a quantity of zero,
+// would otherwise spoil the plot's automatic bounds and blank it for good.
+fn finite_points(points: Vec<[f64; 2]>) -> PlotPoints {
+    PlotPoints::new(points.into_iter().filter(|[x, y]| x.is_finite() && y.is_finite()).collect())
+}
+
+// The smallest region holding every point, with a little room around it.
+fn bounding_box(points: impl Iterator<Item = [f64; 2]>) -> PlotBounds {
+    const MARGIN: f64 = 0.05;
+    let (mut min, mut max) = ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]);
+    for point in points.filter(|[x, y]| x.is_finite() && y.is_finite()) {
+        for axis in 0..2 {
+            min[axis] = min[axis].min(point[axis]);
+            max[axis] = max[axis].max(point[axis]);
+        }
+    }
+    let margin = [(max[0] - min[0]) * MARGIN, (max[1] - min[1]) * MARGIN];
+    PlotBounds::from_min_max([min[0] - margin[0], min[1] - margin[1]], [max[0] + margin[0], max[1] + margin[1]])
+}
+
+// An axis title with its unit in brackets, if there is one.
+fn axis_label(title: &str, unit: &str) -> String {
+    if unit.is_empty() {
+        title.to_owned()
+    } else {
+        format!("{} ({})", title, unit)
+    }
+}
+
+// The inverse supply and demand curves written out with the current
+// parameters, shifts included.
+fn curve_equations(model: &PriceLimitSimulator, lang: Lang) -> (String, String) {
+    let term = |value: f64| {
+        let sign = if value < 0.0 { '−' } else { '+' };
+        format!("{} {}", sign, lang.fmt_num(value.abs()))
+    };
+    // Stretching the quantities scales each power of q by the stretch.
+    let (supply_offset, supply_stretch) = model.supply_transform();
+    let supply = match model.supply_model {
+        SupplyModel::Linear => format!(
+            "S(q) = {}q {}",
+            lang.fmt_num(model.supply_slope * supply_stretch),
+            term(model.supply_intercept + supply_offset)
+        ),
+        SupplyModel::Quadratic { a, b, c } => format!(
+            "S(q) = {}q² {}q {}",
+            lang.fmt_num(a * supply_stretch * supply_stretch),
+            term(b * supply_stretch),
+            term(c + supply_offset)
+        ),
+        SupplyModel::Custom => custom_equation("S", &model.supply_expression, supply_offset, supply_stretch, lang),
+    };
+    let (demand_offset, demand_stretch) = model.demand_transform();
+    let demand = match model.demand_model {
+        DemandModel::Linear => format!(
+            "D(q) = {}q {}",
+            lang.fmt_num(model.demand_slope * demand_stretch),
+            term(model.demand_intercept + demand_offset)
+        ),
+        DemandModel::ConstantElasticity { a, epsilon } => format!(
+            "D(q) = {}·q^(−1/{}) {}",
+            lang.fmt_num(a * demand_stretch.powf(-1.0 / epsilon)),
+            lang.fmt_num(epsilon),
+            term(demand_offset)
+        ),
+        DemandModel::Custom => custom_equation("D", &model.demand_expression, demand_offset, demand_stretch, lang),
+        // Added up in quantities, not prices, so each group is written out.
+        DemandModel::TwoGroups { slope, intercept } => format!(
+            "D⁻¹(p) = D₁⁻¹(p) + D₂⁻¹(p), D₁(q) = {}q {}, D₂(q) = {}q {}",
+            lang.fmt_num(model.demand_slope * demand_stretch),
+            term(model.demand_intercept + demand_offset),
+            lang.fmt_num(slope * demand_stretch),
+            term(intercept + demand_offset)
+        ),
+    };
+    (supply, demand)
+}
+
+// How a binding control sets the traded quantity, step by step with the
+// current numbers: the price the rationed side faces, the curve set equal to
+// it, and that curve solved for the quantity. Empty unless a control binds.
+fn binding_derivation(model: &PriceLimitSimulator, result: &SurplusResult, lang: Lang) -> Vec<String> {
+    let num = |value: f64| lang.fmt_num(value);
+    let (supply_equation, demand_equation) = curve_equations(model, lang);
+    // Where nothing is traded the curve misses the price altogether.
+    let clamped = |solution: String| {
+        if result.quantity > 0.0 { solution } else { format!("max(0, {solution})") }
+    };
+    if !result.binding {
+        return Vec::new();
+    }
+    match model.price_control {
+        PriceControl::Ceiling if result.quantity == model.supply_quantity(result.seller_price) => {
+            let price = result.seller_price;
+            let mut seller_price = num(model.price_limit);
+            if model.tax() > 0.0 {
+                seller_price.push_str(&format!(" − {}", num(model.tax())));
+            }
+            if model.subsidy() > 0.0 {
+                seller_price.push_str(&format!(" + {}", num(model.subsidy())));
+            }
+            if price != model.price_limit {
+                seller_price.push_str(&format!(" = {}", num(price)));
+            }
+            let mut lines = vec![
+                format!("{} p_s = {}", lang.t("ceiling_binds_sellers"), seller_price),
+                format!("{} {} = {}", lang.t("solve_for_quantity"), supply_equation, num(price)),
+            ];
+            let (offset, stretch) = model.supply_transform();
+            lines.push(match model.supply_model {
+                SupplyModel::Linear => format!(
+                    "q = {} = {}",
+                    clamped(format!(
+                        "({} − {}) / {}",
+                        num(price),
+                        num(model.supply_intercept + offset),
+                        num(model.supply_slope * stretch)
+                    )),
+                    num(result.quantity)
+                ),
+                SupplyModel::Quadratic { a, b, c } => {
+                    let (a, b, c) = (a * stretch * stretch, b * stretch, c + offset);
+                    let (a, b, c, price) = (num(a), num(b), num(c), num(price));
+                    let solution = format!("(−{b} + √({b}² + 4·{a}·({price} − {c}))) / (2·{a})");
+                    format!("q = {} = {}", clamped(solution), num(result.quantity))
+                }
+                SupplyModel::Custom => format!("q = S⁻¹({}) = {}", num(price), num(result.quantity)),
+            });
+            lines
+        }
+        PriceControl::Floor => {
+            let price = result.price;
+            let mut lines = vec![
+                format!("{} p_b = {}", lang.t("floor_binds_buyers"), num(price)),
+                format!("{} {} = {}", lang.t("solve_for_quantity"), demand_equation, num(price)),
+            ];
+            let (offset, stretch) = model.demand_transform();
+            lines.push(match model.demand_model {
+                DemandModel::Linear => format!(
+                    "q = {} = {}",
+                    clamped(format!(
+                        "({} − {}) / {}",
+                        num(price),
+                        num(model.demand_intercept + offset),
+                        num(model.demand_slope * stretch)
+                    )),
+                    num(result.quantity)
+                ),
+                DemandModel::ConstantElasticity { a, epsilon } => format!(
+                    "q = (({} − {}) / {})^(−{}) = {}",
+                    num(price),
+                    num(offset),
+                    num(a * stretch.powf(-1.0 / epsilon)),
+                    num(epsilon),
+                    num(result.quantity)
+                ),
+                DemandModel::Custom | DemandModel::TwoGroups { .. } => {
+                    format!("q = D⁻¹({}) = {}", num(price), num(result.quantity))
+                }
+            });
+            lines
+        }
+        PriceControl::Quota => vec![
+            lang.t("quota_binds").to_owned(),
+            format!("q = q̄ = {}", num(result.quantity)),
+            format!("p_b = D({}) = {}", num(result.quantity), num(result.price)),
+        ],
+        _ => Vec::new(),
+    }
+}
+
+// The surplus calculation worked out in LaTeX, for an answer key: the
+// curves, the equilibrium they solve to, whether the intervention binds, and
+// each area with the current numbers put in. A fragment for an amsmath
+// document, always with English decimal points.
+fn surplus_latex(model: &PriceLimitSimulator, result: &SurplusResult) -> String {
+    let num = |value: f64| Lang::ENGLISH.fmt_num(value);
+    let term = |value: f64| format!("{} {}", if value < 0.0 { '-' } else { '+' }, num(value.abs()));
+    let (supply_offset, supply_stretch) = model.supply_transform();
+    let supply = match model.supply_model {
+        SupplyModel::Linear => format!(
+            "{}q {}",
+            num(model.supply_slope * supply_stretch),
+            term(model.supply_intercept + supply_offset)
+        ),
+        SupplyModel::Quadratic { a, b, c } => format!(
+            "{}q^2 {}q {}",
+            num(a * supply_stretch * supply_stretch),
+            term(b * supply_stretch),
+            term(c + supply_offset)
+        ),
+        SupplyModel::Custom => latex_custom(&model.supply_expression, supply_offset, supply_stretch),
+    };
+    let (demand_offset, demand_stretch) = model.demand_transform();
+    let demand = match model.demand_model {
+        DemandModel::Linear => format!(
+            "{}q {}",
+            num(model.demand_slope * demand_stretch),
+            term(model.demand_intercept + demand_offset)
+        ),
+        DemandModel::ConstantElasticity { a, epsilon } => format!(
+            "{}\\,q^{{-1/{}}} {}",
+            num(a * demand_stretch.powf(-1.0 / epsilon)),
+            num(epsilon),
+            term(demand_offset)
+        ),
+        DemandModel::Custom => latex_custom(&model.demand_expression, demand_offset, demand_stretch),
+        DemandModel::TwoGroups { slope, intercept } => format!(
+            "\\text{{the sideways sum of }} {}q {} \\text{{ and }} {}q {}",
+            num(model.demand_slope * demand_stretch),
+            term(model.demand_intercept + demand_offset),
+            num(slope * demand_stretch),
+            term(intercept + demand_offset)
+        ),
+    };
+    let (quantity, buyer_price, seller_price) = (result.quantity, result.price, result.seller_price);
+    let mut latex = String::new();
+    latex.push_str("\\begin{align*}\n");
+    latex.push_str(&format!("S(q) &= {supply} \\\\\n"));
+    latex.push_str(&format!("D(q) &= {demand}\n"));
+    latex.push_str("\\end{align*}\n");
+    latex.push_str(&format!(
+        "Setting $D(q) = S(q)$ gives the equilibrium $q^* = {}$, $p^* = {}$.\n\n",
+        num(result.equilibrium_quantity),
+        num(result.equilibrium_price)
+    ));
+    let verdict = if result.binding { "binds" } else { "does not bind" };
+    let control = match model.price_control {
+        PriceControl::Ceiling => Some(format!(
+            "The ceiling $\\bar p = {}$ {} against $p^* = {}$",
+            num(model.price_limit),
+            verdict,
+            num(result.equilibrium_price)
+        )),
+        PriceControl::Floor => Some(format!(
+            "The floor $\\bar p = {}$ {} against $p^* = {}$",
+            num(model.price_limit),
+            verdict,
+            num(result.equilibrium_price)
+        )),
+        PriceControl::Quota => Some(format!(
+            "The quota $\\bar q = {}$ {} against $q^* = {}$",
+            num(model.quota),
+            verdict,
+            num(result.equilibrium_quantity)
+        )),
+        PriceControl::None => None,
+    };
+    if let Some(control) = control {
+        latex.push_str(&format!("{control}, so $q = {}$ is traded.\n\n", num(quantity)));
+    }
+    if buyer_price != seller_price {
+        latex.push_str(&format!(
+            "With a tax of ${}$ and a subsidy of ${}$ per unit, buyers pay $p_b = {}$ and sellers receive \
+             $p_s = {}$.\n\n",
+            num(model.tax()),
+            num(model.subsidy()),
+            num(buyer_price),
+            num(seller_price)
+        ));
+    }
+    // The same closed forms as the model uses, and integrals where it has none.
+    let straight_demand = model.demand_model == DemandModel::Linear;
+    let straight_supply = model.supply_model == SupplyModel::Linear && model.supply_is_straight();
+    latex.push_str("\\begin{align*}\n");
+    let consumer_surplus = if straight_demand {
+        format!(
+            "\\tfrac12 \\cdot {} \\cdot \\bigl(({} - {}) + ({} - {})\\bigr)",
+            num(quantity),
+            num(model.demand_formula(0.0)),
+            num(buyer_price),
+            num(model.demand_formula(quantity)),
+            num(buyer_price)
+        )
+    } else {
+        format!("\\int_0^{{{}}} \\bigl(D(q) - {}\\bigr)\\,dq", num(quantity), num(buyer_price))
+    };
+    latex.push_str(&format!("CS &= {consumer_surplus} = {} \\\\\n", num(result.consumer_surplus)));
+    let producer_surplus = if straight_supply {
+        format!(
+            "\\tfrac12 \\cdot {} \\cdot \\bigl(({} - {}) + ({} - {})\\bigr)",
+            num(quantity),
+            num(seller_price),
+            num(model.supply_formula(0.0)),
+            num(seller_price),
+            num(model.supply_formula(quantity))
+        )
+    } else {
+        format!("\\int_0^{{{}}} \\bigl({} - S(q)\\bigr)\\,dq", num(quantity), num(seller_price))
+    };
+    latex.push_str(&format!("PS &= {producer_surplus} = {} \\\\\n", num(result.producer_surplus)));
+    let deadweight_loss = if straight_demand && straight_supply {
+        format!(
+            "\\tfrac12 \\cdot ({} - {}) \\cdot ({} - {})",
+            num(result.equilibrium_quantity),
+            num(quantity),
+            num(model.demand_formula(quantity)),
+            num(model.supply_formula(quantity))
+        )
+    } else {
+        format!(
+            "\\int_{{{}}}^{{{}}} \\bigl(D(q) - S(q)\\bigr)\\,dq",
+            num(quantity),
+            num(result.equilibrium_quantity)
+        )
+    };
+    latex.push_str(&format!("DWL &= {deadweight_loss} = {}", num(result.deadweight_loss)));
+    for (name, factor, value) in [
+        ("T", model.tax(), result.tax_revenue),
+        ("G", model.subsidy(), result.subsidy_cost),
+    ] {
+        if factor > 0.0 {
+            latex.push_str(&format!(" \\\\\n{name} &= {} \\cdot {} = {}", num(factor), num(quantity), num(value)));
+        }
+    }
+    if result.waiting_cost > 0.0 {
+        latex.push_str(&format!(
+            " \\\\\nW &= {} \\cdot CS_{{\\text{{rationed}}}} = {}",
+            num(model.waiting_cost_fraction),
+            num(result.waiting_cost)
+        ));
+    }
+    latex.push_str("\n\\end{align*}\n");
+    latex
+}
+
+// A typed formula as LaTeX source, set in type as it was typed, with the
+// shift applied as in `custom_equation`.
+fn latex_custom(expression: &str, offset: f64, stretch: f64) -> String {
+    let escaped = expression.replace('^', "\\^{}");
+    let sign = if offset < 0.0 { '-' } else { '+' };
+    let shifted = format!("{sign} {}", Lang::ENGLISH.fmt_num(offset.abs()));
+    if stretch == 1.0 {
+        format!("\\texttt{{{escaped}}} {shifted}")
+    } else {
+        format!("f({}q) {shifted}, \\quad f(q) = \\texttt{{{escaped}}}", Lang::ENGLISH.fmt_num(stretch))
+    }
+}
+
+// The curves of another market, dashed, over the main plot.
+fn overlay_market(
+    plot_ui: &mut PlotUi,
+    market: &PriceLimitSimulator,
+    (x_max, samples): (f64, usize),
+    (supply_name, demand_name): (String, String),
+    color: egui::Color32,
+) {
+    let curves = [
+        (market.supply_points(x_max, samples), supply_name),
+        (market.demand_points(x_max, samples), demand_name),
+    ];
+    for (points, name) in curves {
+        let line = Line::new(finite_points(points)).name(name).color(color);
+        plot_ui.line(line.style(LineStyle::dashed_loose()));
+    }
+}
+
+fn warning_key(warning: NumericWarning) -> &'static str {
+    match warning {
+        NumericWarning::NotFinite => "warning_not_finite",
+        NumericWarning::QuantityCapped => "warning_quantity_capped",
+        NumericWarning::WillingnessToPayCapped => "warning_willingness_capped",
+        NumericWarning::NegativeLimit => "warning_negative_limit",
+    }
+}
+
+// A typed formula f, written as the curve `name` once shifted: moved up by
+// `offset` and, when rotated, taken at the stretched quantity.
+fn custom_equation(name: &str, expression: &str, offset: f64, stretch: f64, lang: Lang) -> String {
+    let sign = if offset < 0.0 { '−' } else { '+' };
+    let shifted = format!("{sign} {}", lang.fmt_num(offset.abs()));
+    if stretch == 1.0 {
+        format!("{name}(q) = {expression} {shifted}")
+    } else {
+        format!("{name}(q) = f({}q) {shifted}, f(q) = {expression}", lang.fmt_num(stretch))
+    }
+}
+
+// The text field for a typed formula. Text that parses replaces the market's
+// formula; otherwise the error shows and the market keeps the formula it had.
+fn formula_input(ui: &mut egui::Ui, lang: Lang, label: &str, input: &mut FormulaInput, expression: &mut String) {
+    // Undo, presets and loaded scenarios change the formula underneath.
+    if input.error.is_none() && input.text != *expression {
+        input.text = expression.clone();
+    }
+    ui.horizontal(|ui| {
+        ui.label(label);
+        if ui.text_edit_singleline(&mut input.text).changed() {
+            match input.text.parse::<Formula>() {
+                Ok(_) => {
+                    expression.clone_from(&input.text);
+                    input.error = None;
+                }
+                Err(err) => input.error = Some(err),
+            }
+        }
+    });
+    if let Some(err) = &input.error {
+        ui.colored_label(egui::Color32::RED, format!("{}: {}", lang.t("formula_error"), err));
+    }
+}
+
+// The centre of mass of a simple polygon, or `None` when it encloses no area
+// to label.
+fn centroid(outline: &[[f64; 2]]) -> Option<PlotPoint> {
+    let (mut area, mut x, mut y) = (0.0, 0.0, 0.0);
+    for (i, [x0, y0]) in outline.iter().enumerate() {
+        let [x1, y1] = outline[(i + 1) % outline.len()];
+        let cross = x0 * y1 - x1 * y0;
+        area += cross;
+        x += (x0 + x1) * cross;
+        y += (y0 + y1) * cross;
+    }
+    (area.abs() > 1e-9).then(|| PlotPoint::new(x / (3.0 * area), y / (3.0 * area)))
+}
+
+// A slider paired with a box for typing an exact value, both bound to the
+// same range.
+fn slider_with_input(ui: &mut egui::Ui, value: &mut f64, range: RangeInclusive<f64>, label: &str) -> egui::Response {
+    ui.horizontal(|ui| {
+        let slider = ui.add(egui::Slider::new(value, range.clone()).show_value(false));
+        let input = ui.add(egui::DragValue::new(value).range(range).speed(0.05));
+        // The label comes last on screen but names both for screen readers.
+        let label = ui.label(label);
+        slider.labelled_by(label.id) | input.labelled_by(label.id)
+    })
+    .inner
+}
+
+// A strip under the price limit slider, coloured as the binding badge over
+// the limits that bind and grey over the rest, split at `threshold` of a
+// slider running from zero to `max`.
+fn binding_track(ui: &mut egui::Ui, threshold: f64, max: f64, binds_below: bool) -> egui::Response {
+    let size = egui::vec2(ui.spacing().slider_width, 4.0);
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+    // The slider's rail stops short of its ends by the handle's radius.
+    let rail = rect.shrink2(egui::vec2(ui.spacing().interact_size.y / 2.5, 0.0));
+    let split = rail.left() + rail.width() * (threshold / max).clamp(0.0, 1.0) as f32;
+    let below = egui::Rect::from_min_max(rect.min, egui::pos2(split, rect.max.y));
+    let above = egui::Rect::from_min_max(egui::pos2(split, rect.min.y), rect.max);
+    let (binding, free) = (egui::Color32::from_rgb(200, 60, 0), egui::Color32::GRAY);
+    let (below_color, above_color) = if binds_below { (binding, free) } else { (free, binding) };
+    ui.painter().rect_filled(below, 1.0, below_color);
+    ui.painter().rect_filled(above, 1.0, above_color);
+    response
+}
+
+// Names a widget whose own text is only a symbol, for screen readers and as a
+// tooltip.
+fn describe(response: egui::Response, description: &str, selected: bool) -> egui::Response {
+    let kind = egui::WidgetType::SelectableLabel;
+    response.widget_info(|| egui::WidgetInfo::selected(kind, true, selected, description));
+    response.on_hover_text(description)
+}
+
+// The centres and counts of equal bins spanning `values`, with the bins'
+// width. Values that are all the same share one narrow bin.
+fn histogram(values: &[f64]) -> (Vec<[f64; 2]>, f64) {
+    const BINS: usize = 25;
+    if values.is_empty() {
+        return (Vec::new(), 1.0);
+    }
+    let (min, max) = values.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+        (min.min(value), max.max(value))
+    });
+    if max - min < 1e-9 {
+        return (vec![[min, values.len() as f64]], 0.1);
+    }
+    let width = (max - min) / BINS as f64;
+    let mut counts = vec![0.0; BINS];
+    for value in values {
+        counts[(((value - min) / width) as usize).min(BINS - 1)] += 1.0;
+    }
+    let centres = (0..BINS).map(|bin| min + (bin as f64 + 0.5) * width);
+    (centres.zip(counts).map(|(centre, count)| [centre, count]).collect(), width)
+}
+
+// Gives `plot` the legend, unless legends are hidden.
+fn with_legend(plot: Plot<'_>, legend: Option<Legend>) -> Plot<'_> {
+    match legend {
+        Some(legend) => plot.legend(legend),
+        None => plot,
+    }
+}
+
+// A saved parameter as the diff against the snapshot shows it: numbers as
+// everywhere else, names of choices bare, and a dash where one side has none.
+fn fmt_value(lang: Lang, value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Number(number) => number.as_f64().map_or(number.to_string(), |n| lang.fmt_num(n)),
+        serde_json::Value::String(name) => name.clone(),
+        serde_json::Value::Null => "—".to_owned(),
+        other => other.to_string(),
+    }
+}
+
+// The translation key naming a legend position.
+fn corner_name(corner: Corner) -> &'static str {
+    match corner {
+        Corner::LeftTop => "top_left",
+        Corner::RightTop => "top_right",
+        Corner::LeftBottom => "bottom_left",
+        Corner::RightBottom => "bottom_right",
+    }
+}
+
+// Rounds `value` to the nearest multiple of `step`, leaving it alone for a
+// step too small to mean anything.
+fn snap_to_step(value: f64, step: f64) -> f64 {
+    if step > 0.0 {
+        (value / step).round() * step
+    } else {
+        value
+    }
+}
+
+// Moves `value` a frame's worth of the way to `target`, returning whether it
+// still has further to go.
+fn ease_toward(value: &mut f64, target: f64, dt: f64) -> bool {
+    // Closing the gap exponentially with this time constant leaves under one
+    // percent of it after half a second.
+    const TIME_CONSTANT: f64 = 0.1;
+    if (target - *value).abs() < 1e-3 {
+        *value = target;
+        return false;
+    }
+    *value += (target - *value) * (1.0 - (-dt / TIME_CONSTANT).exp());
+    true
+}
+
+impl eframe::App for SimulatorApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, self);
+    }
+
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+        let frame_started = std::time::Instant::now();
+        #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+        {
+            self.timings = FrameTimings::default();
+        }
+        if ctx.style().visuals.dark_mode != self.dark_mode {
+            ctx.set_visuals(if self.dark_mode { egui::Visuals::dark() } else { egui::Visuals::light() });
+        }
+        if !ctx.wants_keyboard_input() {
+            let (undo, redo, help) = ctx.input_mut(|input| {
+                let undo = input.consume_key(egui::Modifiers::COMMAND, egui::Key::Z);
+                let redo = input.consume_key(egui::Modifiers::COMMAND, egui::Key::Y)
+                    || input.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Z);
+                let help = input.consume_key(egui::Modifiers::NONE, egui::Key::F1);
+                (undo, redo, help)
+            });
+            if undo {
+                self.undo();
+            }
+            if redo {
+                self.redo();
+            }
+            if help {
+                self.show_help = !self.show_help;
+            }
+        }
+        self.nudge(ctx);
+        self.throttle_recompute(ctx);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let screenshot = ctx.input(|input| {
+                input.events.iter().find_map(|event| match event {
+                    egui::Event::Screenshot { image, .. } => Some(image.clone()),
+                    _ => None,
+                })
+            });
+            if let Some(screenshot) = screenshot {
+                let lang = self.lang();
+                self.status = match self.export_png(&screenshot, ctx.pixels_per_point(), &self.png_path) {
+                    Ok(()) => format!("{} {}", lang.t("exported_to"), self.png_path),
+                    Err(err) => format!("{}: {}", lang.t("export_failed"), err),
+                };
+            }
+        }
+        if self.ceiling_lift.as_ref().is_some_and(|lift| {
+            lift.current != self.model.price_limit || self.model.price_control != PriceControl::Ceiling
+        }) {
+            self.ceiling_lift = None;
+        }
+        if let Some(lift) = &mut self.ceiling_lift {
+            let dt = ctx.input(|input| input.stable_dt) as f64;
+            match lift.advance(dt) {
+                Some(price_limit) => {
+                    self.model.price_limit = price_limit;
+                    ctx.request_repaint_after(self.frame_interval());
+                }
+                None => {
+                    self.model.price_limit = lift.to;
+                    self.ceiling_lift = None;
+                }
+            }
+        }
+        // Moving the ceiling or the control by hand takes over from the script.
+        if self.ratchet.as_ref().is_some_and(|ratchet| {
+            Ratchet::STEPS[ratchet.step].0 != self.model.price_limit
+                || self.model.price_control != PriceControl::Ceiling
+        }) {
+            self.ratchet = None;
+        }
+        if let Some(ratchet) = self.ratchet.as_mut().filter(|ratchet| ratchet.playing) {
+            ratchet.elapsed += ctx.input(|input| input.stable_dt) as f64;
+            if ratchet.elapsed >= Ratchet::SECONDS {
+                let next = ratchet.step + 1;
+                self.go_to_ratchet_step(next);
+            } else {
+                ctx.request_repaint_after(std::time::Duration::from_secs_f64(Ratchet::SECONDS - ratchet.elapsed));
+            }
+        }
+        // Playing back takes the market from the recording, up to the last
+        // change made by the time elapsed so far.
+        if let Some(replay) = &mut self.replay {
+            replay.elapsed += ctx.input(|input| input.stable_dt) as f64;
+            let mut shown = None;
+            while let Some((time, market)) = self.recording.get(replay.next) {
+                if *time > replay.elapsed {
+                    break;
+                }
+                shown = Some(market.clone());
+                replay.next += 1;
+            }
+            if replay.next < self.recording.len() {
+                ctx.request_repaint_after(self.frame_interval());
+            } else {
+                self.replay = None;
+            }
+            if let Some(market) = shown {
+                self.model = market;
+                self.settle_shifts();
+            }
+        }
+        if self.animate_shifts {
+            let dt = ctx.input(|input| input.stable_dt) as f64;
+            let supply_moving = ease_toward(&mut self.model.supply_shift, self.supply_shift_target, dt);
+            let demand_moving = ease_toward(&mut self.model.demand_shift, self.demand_shift_target, dt);
+            if supply_moving || demand_moving {
+                ctx.request_repaint_after(self.frame_interval());
+            }
+        } else {
+            self.settle_shifts();
+        }
+        if let Some(since) = self.recording_since {
+            if self.recording.last().is_none_or(|(_, market)| *market != self.model) {
+                let time = ctx.input(|input| input.time) - since;
+                self.recording.push((time, self.model.clone()));
+            }
+        }
+        #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+        let surplus_started = std::time::Instant::now();
+        let result = self.model.calculate_surplus();
+        #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+        {
+            self.timings.surplus += surplus_started.elapsed();
+        }
+        if self.was_binding && !result.binding {
+            self.clears_flash = Some(0.0);
+        }
+        self.was_binding = result.binding;
+        if self.model.is_valid() {
+            let total_surplus = result.total_surplus();
+            if self.lowest_surplus.as_ref().is_none_or(|record| total_surplus < record.total_surplus) {
+                self.lowest_surplus = Some(SurplusRecord { total_surplus, market: self.model.clone() });
+            }
+            if self.highest_surplus.as_ref().is_none_or(|record| total_surplus > record.total_surplus) {
+                self.highest_surplus = Some(SurplusRecord { total_surplus, market: self.model.clone() });
+            }
+        }
+        if let Some(elapsed) = &mut self.clears_flash {
+            *elapsed += ctx.input(|input| input.stable_dt) as f64;
+            if *elapsed < CLEARS_FLASH_SECONDS {
+                ctx.request_repaint_after(self.frame_interval());
+            } else {
+                self.clears_flash = None;
+            }
+        }
+        let palette = Palette::new(self.dark_mode, self.colorblind_mode);
+
+        let lang = self.lang();
+        let (supply_equation, demand_equation) = curve_equations(&self.model, lang);
+        #[cfg(not(target_arch = "wasm32"))]
+        self.confirm_close_if_unsaved(ctx);
+
+        egui::Window::new(lang.t("help_title"))
+            .open(&mut self.show_help)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(lang.t("help_supply"));
+                ui.monospace(supply_equation);
+                ui.label(lang.t("help_demand"));
+                ui.monospace(demand_equation);
+                ui.label(lang.t("help_controls"));
+                ui.label(lang.t("help_surplus"));
+                ui.monospace("CS = ∫ (D(q) − P_buyer) dq");
+                ui.monospace("PS = ∫ (P_seller − S(q)) dq");
+                ui.monospace("DWL = ∫ (D(q) − S(q)) dq, q_traded ≤ q ≤ q*");
+                ui.label(lang.t("help_linear"));
+            });
+
+        egui::Window::new(lang.t("settings")).open(&mut self.show_settings).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(lang.t("repaint"));
+                ui.radio_value(&mut self.continuous_repaint, false, lang.t("reactive"));
+                ui.radio_value(&mut self.continuous_repaint, true, lang.t("continuous"));
+            });
+            ui.add_enabled_ui(self.continuous_repaint || self.animate_shifts, |ui| {
+                ui.horizontal(|ui| {
+                    let label = ui.label(lang.t("max_fps"));
+                    ui.add(egui::DragValue::new(&mut self.max_fps).range(1.0..=240.0)).labelled_by(label.id);
+                });
+            });
+            ui.horizontal(|ui| {
+                let label = ui.label(lang.t("curve_samples"));
+                let samples = egui::Slider::new(&mut self.curve_samples, 20..=1000).logarithmic(true);
+                ui.add(samples).labelled_by(label.id);
+            });
+            ui.label(lang.t("line_width"));
+            egui::Grid::new("line_looks").show(ui, |ui| {
+                for (key, look) in [
+                    ("supply_curve", &mut self.supply_look),
+                    ("demand_curve", &mut self.demand_look),
+                    ("price_limit", &mut self.limit_look),
+                ] {
+                    let label = ui.label(lang.t(key));
+                    look.edit(ui, lang, label.id);
+                    ui.end_row();
+                }
+            });
+        });
+
+        // Figures the model had to clamp, or could not compute at all.
+        let result = self.model.calculate_surplus();
+        let warnings = self.model.numeric_warnings(&result);
+        // Debug builds also report surplus areas that do not add up, which
+        // means one of them is computed wrong; numerical integration of
+        // curved markets leaves a little over.
+        let residual = self.model.surplus_residual(&result);
+        let tolerance = 1e-3 * (1.0 + result.total_surplus().abs());
+        let mismatch = cfg!(debug_assertions) && self.model.is_valid() && residual.abs() > tolerance;
+        if !warnings.is_empty() || mismatch {
+            egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+                for warning in warnings {
+                    ui.colored_label(egui::Color32::from_rgb(200, 120, 0), lang.t(warning_key(warning)));
+                }
+                if mismatch {
+                    let text = format!("{} {}", lang.t("surplus_residual"), lang.fmt_num(residual));
+                    ui.colored_label(egui::Color32::from_rgb(200, 40, 40), text);
+                }
+            });
+        }
+
+        // Below this width the plot would be squeezed out by the controls, so
+        // they move to a side panel of their own.
+        const COMPACT_WIDTH: f32 = 900.0;
+        let compact = self.compact || ctx.screen_rect().width() < COMPACT_WIDTH;
+        if compact {
+            egui::SidePanel::left("controls").resizable(true).show(ctx, |ui| {
+                egui::ScrollArea::both().show(ui, |ui| self.show_controls(ui, compact));
+            });
+            egui::CentralPanel::default().show(ctx, |ui| self.show_plots(ui, &palette));
+        } else {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                self.show_controls(ui, compact);
+                self.show_plots(ui, &palette);
+            });
+        }
+        self.record_history(ctx);
+        if self.continuous_repaint {
+            ctx.request_repaint_after(self.frame_interval());
+        }
+        #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+        {
+            let millis = |duration: std::time::Duration| duration.as_secs_f64() * 1000.0;
+            let text = format!(
+                "frame {:.2} ms · surplus {:.2} ms · sampling {:.2} ms",
+                millis(frame_started.elapsed()),
+                millis(self.timings.surplus),
+                millis(self.timings.sampling)
+            );
+            egui::Area::new(egui::Id::new("frame_timings"))
+                .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -8.0))
+                .interactable(false)
+                .show(ctx, |ui| ui.weak(text));
+        }
+    }
+}
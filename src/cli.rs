@@ -0,0 +1,227 @@
+use prijsplafond::model::{PriceControl, PriceLimitSimulator, SurplusResult};
+use serde::Serialize;
+
+const USAGE: &str = "usage: prijsplafond [--control ceiling|floor|quota|none] [--price-limit P] [--quota Q]
+                    [--tax T] [--subsidy S] [--supply-slope B] [--supply-intercept A]
+                    [--supply-shift X] [--demand-slope B] [--demand-intercept A]
+                    [--demand-shift X] [--json]
+       prijsplafond --json-stdin
+       prijsplafond --scenario FILE
+
+Without arguments the graphical simulator starts, on the market in the TOML
+file named by PRIJSPLAFOND_CONFIG or else in prijsplafond.toml if there is
+one, and with `--scenario` it starts on the market saved in FILE. `--json-stdin` reads one
+market as a JSON object from standard input, with the fields of a saved
+scenario, any of them left out keeping its default, and prints its outcome as
+a JSON object.";
+
+// The outcome `--json-stdin` prints. Graders depend on these names, so fields
+// may be added but never renamed or removed: every field of `SurplusResult`
+// (consumer_surplus, producer_surplus, quantity, price, seller_price,
+// deadweight_loss, waiting_cost, tax_revenue, subsidy_cost,
+// equilibrium_quantity, equilibrium_price, binding) at the top level, then
+// the ones below.
+#[derive(Serialize)]
+struct Graded {
+    #[serde(flatten)]
+    outcome: SurplusResult,
+    total_surplus: f64,
+    // Units buyers want at the price they pay but cannot get.
+    shortage: f64,
+    // False for a market without a proper crossing, whose figures mean nothing.
+    valid: bool,
+}
+
+// A market from a JSON object with the fields of a saved scenario.
+fn parse_market(input: &str) -> Result<PriceLimitSimulator, String> {
+    serde_json::from_str(input).map_err(|err| format!("invalid market: {err}"))
+}
+
+// The market saved in the scenario file at `path`, for the window to open on.
+pub fn read_scenario(path: &str) -> Result<PriceLimitSimulator, String> {
+    let input = std::fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+    parse_market(&input).map_err(|err| format!("{path}: {err}"))
+}
+
+// Read from the working directory when PRIJSPLAFOND_CONFIG names no other file.
+const CONFIG_FILE: &str = "prijsplafond.toml";
+
+// The market the window opens on without `--scenario`, so that lab computers
+// all start in the same state. `None` without a config file.
+pub fn read_config() -> Result<Option<PriceLimitSimulator>, String> {
+    let path = match std::env::var("PRIJSPLAFOND_CONFIG") {
+        Ok(path) => path,
+        Err(_) if std::path::Path::new(CONFIG_FILE).exists() => CONFIG_FILE.to_owned(),
+        Err(_) => return Ok(None),
+    };
+    let input = std::fs::read_to_string(&path).map_err(|err| format!("{path}: {err}"))?;
+    parse_config(&input).map(Some).map_err(|err| format!("{path}: {err}"))
+}
+
+// A market from TOML with the fields of a saved scenario, read through the
+// same deserializer as the JSON by way of its JSON equivalent.
+fn parse_config(input: &str) -> Result<PriceLimitSimulator, String> {
+    let document: toml_edit::DocumentMut = input.parse().map_err(|err| format!("invalid config: {err}"))?;
+    serde_json::from_value(toml_table_to_json(document.as_table()))
+        .map_err(|err| format!("invalid market: {err}"))
+}
+
+fn toml_table_to_json<'a>(
+    entries: impl IntoIterator<Item = (&'a str, &'a toml_edit::Item)>,
+) -> serde_json::Value {
+    let entries = entries.into_iter().filter_map(|(key, item)| {
+        let value = match item {
+            toml_edit::Item::None => return None,
+            toml_edit::Item::Value(value) => toml_value_to_json(value),
+            toml_edit::Item::Table(table) => toml_table_to_json(table),
+            toml_edit::Item::ArrayOfTables(tables) => tables.iter().map(toml_table_to_json).collect(),
+        };
+        Some((key.to_owned(), value))
+    });
+    serde_json::Value::Object(entries.collect())
+}
+
+fn toml_value_to_json(value: &toml_edit::Value) -> serde_json::Value {
+    match value {
+        toml_edit::Value::String(text) => text.value().clone().into(),
+        toml_edit::Value::Integer(number) => (*number.value()).into(),
+        toml_edit::Value::Float(number) => (*number.value()).into(),
+        toml_edit::Value::Boolean(flag) => (*flag.value()).into(),
+        toml_edit::Value::Datetime(date) => date.value().to_string().into(),
+        toml_edit::Value::Array(values) => values.iter().map(toml_value_to_json).collect(),
+        toml_edit::Value::InlineTable(table) => serde_json::Value::Object(
+            table.iter().map(|(key, value)| (key.to_owned(), toml_value_to_json(value))).collect(),
+        ),
+    }
+}
+
+// Evaluates the market described by the JSON object `input`.
+fn grade(input: &str) -> Result<String, String> {
+    let model = parse_market(input)?;
+    model.log_state();
+    let outcome = model.calculate_surplus();
+    let graded = Graded {
+        total_surplus: outcome.total_surplus(),
+        shortage: (model.demand_quantity(outcome.price) - outcome.quantity).max(0.0),
+        valid: model.is_valid(),
+        outcome,
+    };
+    serde_json::to_string_pretty(&graded).map_err(|err| err.to_string())
+}
+
+// Builds a market from the command line and reports its outcome, as plain
+// text or, with `--json`, as a JSON object.
+pub fn run(args: &[String]) -> Result<String, String> {
+    let mut model = PriceLimitSimulator::default();
+    let mut json = false;
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        if flag == "--json" {
+            json = true;
+            continue;
+        }
+        if flag == "--json-stdin" {
+            let input = std::io::read_to_string(std::io::stdin()).map_err(|err| err.to_string())?;
+            return grade(&input);
+        }
+        if flag == "--help" || flag == "-h" {
+            return Ok(USAGE.to_owned());
+        }
+        let value = args.next().ok_or_else(|| format!("{flag} needs a value\n\n{USAGE}"))?;
+        if flag == "--control" {
+            model.price_control = match value.as_str() {
+                "ceiling" => PriceControl::Ceiling,
+                "floor" => PriceControl::Floor,
+                "quota" => PriceControl::Quota,
+                "none" => PriceControl::None,
+                _ => return Err(format!("unknown control `{value}`\n\n{USAGE}")),
+            };
+            continue;
+        }
+        let field = match flag.as_str() {
+            "--price-limit" => &mut model.price_limit,
+            "--quota" => &mut model.quota,
+            "--tax" => &mut model.tax_per_unit,
+            "--subsidy" => &mut model.subsidy_per_unit,
+            "--supply-slope" => &mut model.supply_slope,
+            "--supply-intercept" => &mut model.supply_intercept,
+            "--supply-shift" => &mut model.supply_shift,
+            "--demand-slope" => &mut model.demand_slope,
+            "--demand-intercept" => &mut model.demand_intercept,
+            "--demand-shift" => &mut model.demand_shift,
+            _ => return Err(format!("unknown option `{flag}`\n\n{USAGE}")),
+        };
+        *field = value.parse().map_err(|_| format!("{flag} expects a number, got `{value}`"))?;
+    }
+
+    model.log_state();
+    let result = model.calculate_surplus();
+    if json {
+        return serde_json::to_string_pretty(&result).map_err(|err| err.to_string());
+    }
+    Ok(format!(
+        "consumer_surplus: {:.2}\nproducer_surplus: {:.2}\ndeadweight_loss: {:.2}\nquantity: {:.2}\nprice: {:.2}",
+        result.consumer_surplus, result.producer_surplus, result.deadweight_loss, result.quantity, result.price
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prijsplafond::model::SupplyModel;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_owned).collect()
+    }
+
+    #[test]
+    fn prints_the_binding_ceiling_outcome() {
+        let output = run(&args("--price-limit 8")).unwrap();
+        assert!(output.contains("consumer_surplus: 33.00"));
+        assert!(output.contains("quantity: 6.00"));
+    }
+
+    #[test]
+    fn rejects_unknown_options_and_bad_numbers() {
+        assert!(run(&args("--price-cap 8")).is_err());
+        assert!(run(&args("--price-limit eight")).is_err());
+        assert!(run(&args("--price-limit")).is_err());
+    }
+
+    #[test]
+    fn grades_a_market_read_as_json() {
+        let output: serde_json::Value = serde_json::from_str(&grade(r#"{"price_limit": 8.0}"#).unwrap()).unwrap();
+        assert_eq!(output["consumer_surplus"], 33.0);
+        assert_eq!(output["shortage"], 8.0);
+        assert_eq!(output["binding"], true);
+        assert_eq!(output["valid"], true);
+        assert!(grade("{\"price_limit\": \"eight\"}").is_err());
+    }
+
+    #[test]
+    fn reads_a_market_from_a_toml_config() {
+        let model = parse_config(
+            "price_limit = 8\nprice_control = \"Floor\"\n\n[supply_model.Quadratic]\na = 0.05\nb = 0.2\nc = 5.0\n",
+        )
+        .unwrap();
+        assert_eq!(model.price_limit, 8.0);
+        assert_eq!(model.price_control, PriceControl::Floor);
+        assert_eq!(model.supply_model, SupplyModel::Quadratic { a: 0.05, b: 0.2, c: 5.0 });
+        assert_eq!(model.demand_slope, PriceLimitSimulator::default().demand_slope);
+        assert!(parse_config("price_limit = \"eight\"").is_err());
+        assert!(parse_config("price_limit = ").is_err());
+    }
+
+    #[test]
+    fn reads_a_saved_scenario() {
+        let path = std::env::temp_dir().join(format!("prijsplafond-scenario-{}.json", std::process::id()));
+        let saved = PriceLimitSimulator { price_limit: 8.0, supply_shift: 2.0, ..Default::default() };
+        std::fs::write(&path, serde_json::to_string(&saved).unwrap()).unwrap();
+        let path = path.to_str().unwrap();
+        assert_eq!(read_scenario(path).unwrap(), saved);
+        std::fs::write(path, "not json").unwrap();
+        assert!(read_scenario(path).unwrap_err().starts_with(path));
+        std::fs::remove_file(path).unwrap();
+        assert!(read_scenario(path).is_err());
+    }
+}
@@ -0,0 +1,242 @@
+// Curves typed in by hand: an expression in the quantity `q` with numbers,
+// `+ - * / ^`, brackets and the functions `sqrt`, `exp` and `ln`, such as
+// `0.3*q^2 + 2`. A number or bracket directly before `q`, a function or a
+// bracket multiplies, so `0.3q^2 + 2` means the same.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Formula(Node);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Number(f64),
+    Quantity,
+    Negate(Box<Node>),
+    Binary(Operator, Box<Node>, Box<Node>),
+    Call(Function, Box<Node>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Sqrt,
+    Exp,
+    Ln,
+}
+
+impl Formula {
+    // The price the formula gives at `quantity`.
+    pub fn eval(&self, quantity: f64) -> f64 {
+        self.0.eval(quantity)
+    }
+}
+
+impl std::str::FromStr for Formula {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Formula, String> {
+        let mut parser = Parser { text, position: 0 };
+        let node = parser.sum()?;
+        parser.skip_spaces();
+        match parser.peek() {
+            None => Ok(Formula(node)),
+            Some(c) => Err(parser.error(&format!("unexpected `{c}`"))),
+        }
+    }
+}
+
+impl Node {
+    fn eval(&self, quantity: f64) -> f64 {
+        match self {
+            Node::Number(value) => *value,
+            Node::Quantity => quantity,
+            Node::Negate(node) => -node.eval(quantity),
+            Node::Binary(operator, left, right) => {
+                let (left, right) = (left.eval(quantity), right.eval(quantity));
+                match operator {
+                    Operator::Add => left + right,
+                    Operator::Subtract => left - right,
+                    Operator::Multiply => left * right,
+                    Operator::Divide => left / right,
+                    Operator::Power => left.powf(right),
+                }
+            }
+            Node::Call(function, argument) => {
+                let argument = argument.eval(quantity);
+                match function {
+                    Function::Sqrt => argument.sqrt(),
+                    Function::Exp => argument.exp(),
+                    Function::Ln => argument.ln(),
+                }
+            }
+        }
+    }
+}
+
+// Recursive descent, one method per level of precedence from sums down to
+// single numbers, names and brackets.
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn skip_spaces(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.position += c.len_utf8();
+        }
+    }
+
+    // The next character after any spaces, taken if it is `c`.
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_spaces();
+        let found = self.peek() == Some(c);
+        if found {
+            self.position += c.len_utf8();
+        }
+        found
+    }
+
+    // Positions count from one, as a reader would.
+    fn error(&self, message: &str) -> String {
+        format!("{message} at position {}", self.text[..self.position].chars().count() + 1)
+    }
+
+    fn sum(&mut self) -> Result<Node, String> {
+        let mut node = self.product()?;
+        loop {
+            let operator = if self.eat('+') {
+                Operator::Add
+            } else if self.eat('-') {
+                Operator::Subtract
+            } else {
+                return Ok(node);
+            };
+            node = Node::Binary(operator, Box::new(node), Box::new(self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Result<Node, String> {
+        let mut node = self.unary()?;
+        loop {
+            self.skip_spaces();
+            // Written side by side, as in `2q` or `3(q + 1)`.
+            let adjacent = self.peek().is_some_and(|c| c.is_alphabetic() || c == '(');
+            let operator = if adjacent || self.eat('*') {
+                Operator::Multiply
+            } else if self.eat('/') {
+                Operator::Divide
+            } else {
+                return Ok(node);
+            };
+            node = Node::Binary(operator, Box::new(node), Box::new(self.unary()?));
+        }
+    }
+
+    // A leading minus binds looser than a power, so `-q^2` is `-(q^2)`.
+    fn unary(&mut self) -> Result<Node, String> {
+        if self.eat('-') {
+            return Ok(Node::Negate(Box::new(self.unary()?)));
+        }
+        let base = self.atom()?;
+        if self.eat('^') {
+            // Powers group from the right, `2^3^2` being `2^9`.
+            return Ok(Node::Binary(Operator::Power, Box::new(base), Box::new(self.unary()?)));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Node, String> {
+        if self.eat('(') {
+            let node = self.sum()?;
+            if !self.eat(')') {
+                return Err(self.error("missing `)`"));
+            }
+            return Ok(node);
+        }
+        let start = self.position;
+        match self.peek() {
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+                    self.position += 1;
+                }
+                let number = &self.text[start..self.position];
+                number.parse().map(Node::Number).map_err(|_| {
+                    self.position = start;
+                    self.error(&format!("`{number}` is not a number"))
+                })
+            }
+            Some(c) if c.is_alphabetic() => {
+                while self.peek().is_some_and(char::is_alphanumeric) {
+                    self.position += self.peek().unwrap().len_utf8();
+                }
+                let function = match &self.text[start..self.position] {
+                    "q" => return Ok(Node::Quantity),
+                    "sqrt" => Function::Sqrt,
+                    "exp" => Function::Exp,
+                    "ln" => Function::Ln,
+                    name => {
+                        let name = name.to_owned();
+                        self.position = start;
+                        return Err(self.error(&format!("unknown name `{name}`")));
+                    }
+                };
+                if !self.eat('(') {
+                    return Err(self.error("expected `(`"));
+                }
+                let argument = self.sum()?;
+                if !self.eat(')') {
+                    return Err(self.error("missing `)`"));
+                }
+                Ok(Node::Call(function, Box::new(argument)))
+            }
+            Some(c) => Err(self.error(&format!("unexpected `{c}`"))),
+            None => Err(self.error("expected a number, `q` or `(`")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(text: &str, quantity: f64) -> f64 {
+        text.parse::<Formula>().unwrap().eval(quantity)
+    }
+
+    #[test]
+    fn evaluates_with_the_usual_precedence() {
+        assert_eq!(eval("0.3*q^2 + 2", 10.0), 32.0);
+        assert_eq!(eval("0.3q^2 + 2", 10.0), 32.0);
+        assert_eq!(eval("15 - 0.5 * q", 4.0), 13.0);
+        assert_eq!(eval("-q^2", 3.0), -9.0);
+        assert_eq!(eval("2^3^2", 0.0), 512.0);
+        assert_eq!(eval("12 / 2 / 3", 0.0), 2.0);
+        assert_eq!(eval("3(q + 1)", 1.0), 6.0);
+        assert_eq!(eval("sqrt(q) + ln(exp(2))", 16.0), 6.0);
+        assert_eq!(eval("2\u{a0}* q", 3.0), 6.0);
+    }
+
+    #[test]
+    fn reports_where_parsing_failed() {
+        let error = |text: &str| text.parse::<Formula>().unwrap_err();
+        assert_eq!(error("2 * (q + 1"), "missing `)` at position 11");
+        assert_eq!(error("2 * p"), "unknown name `p` at position 5");
+        assert_eq!(error("q +"), "expected a number, `q` or `(` at position 4");
+        assert_eq!(error("1.2.3"), "`1.2.3` is not a number at position 1");
+        assert_eq!(error("q)"), "unexpected `)` at position 2");
+        assert_eq!(error("sqrt q"), "expected `(` at position 6");
+        assert_eq!(error("q\u{a0}+"), "expected a number, `q` or `(` at position 4");
+    }
+}
@@ -0,0 +1,26 @@
+#[cfg(feature = "gui")]
+mod app;
+pub mod formula;
+pub mod model;
+
+#[cfg(feature = "gui")]
+pub use app::SimulatorApp;
+
+// Runs the simulator on the page's canvas with the given id.
+#[cfg(all(target_arch = "wasm32", feature = "gui"))]
+pub async fn start(canvas_id: &str) -> Result<(), wasm_bindgen::JsValue> {
+    use wasm_bindgen::JsCast;
+
+    let canvas = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id(canvas_id))
+        .ok_or("canvas not found")?
+        .dyn_into::<web_sys::HtmlCanvasElement>()?;
+    eframe::WebRunner::new()
+        .start(
+            canvas,
+            eframe::WebOptions::default(),
+            Box::new(|cc| Ok(Box::new(SimulatorApp::new(cc)))),
+        )
+        .await
+}
@@ -0,0 +1,2364 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::formula::Formula;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PriceControl {
+    Ceiling,
+    Floor,
+    // Caps the traded quantity at `quota` rather than fixing a price.
+    Quota,
+    None,
+}
+
+// Which per-unit intervention applies. Either stacks with any price control,
+// but a tax and a subsidy together would only net out, so only the chosen
+// one counts and the amount of the other waits for it to be chosen again.
+// `Auto`, for markets set up without a choice such as older files, applies
+// a tax if one is set and a subsidy otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Levy {
+    #[default]
+    Auto,
+    Tax,
+    Subsidy,
+    None,
+}
+
+// Largest quantity the model considers, and the widest the plot can be set.
+pub const MAX_QUANTITY: f64 = 100.0;
+// Constant-elasticity demand is unbounded near zero quantity and, for an
+// elasticity of at most one, encloses an infinite consumer surplus. Capping
+// the willingness to pay keeps both finite.
+pub const MAX_WILLINGNESS_TO_PAY: f64 = 30.0;
+const MIN_QUANTITY: f64 = 0.01;
+// Steeper than this, in price per unit, a curve is drawn as a vertical line
+// and its crossing falls between the pixels of any plot.
+pub const MAX_SLOPE: f64 = 1000.0;
+const SCAN_STEPS: usize = 1000;
+// How far apart, relative to the price, the curves may still be where the
+// bisection stops for it to count as the point where they meet.
+const MEETING_TOLERANCE: f64 = 1e-6;
+// How many pieces the curves are tabulated in unless asked otherwise, and
+// what a typed curve is checked at.
+pub const DEFAULT_SAMPLES: usize = 100;
+// Adaptive sampling starts from the requested number of segments, or only a
+// few for a straight curve, enough to catch the kink where it meets zero,
+// and halves any segment whose midpoint strays further than the tolerance
+// from the chord.
+const STRAIGHT_SEGMENTS: usize = 4;
+const MAX_REFINEMENTS: u32 = 8;
+const SAMPLE_TOLERANCE: f64 = 0.005;
+// How close a control may be to where it starts to bind and still count as
+// set right there: within the rounding of the figures shown, to the cent.
+pub const AT_THRESHOLD: f64 = 0.005;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SupplyModel {
+    // Uses `supply_slope` and `supply_intercept`.
+    Linear,
+    // price = a * quantity^2 + b * quantity + c, a rising marginal cost.
+    Quadratic { a: f64, b: f64, c: f64 },
+    // Typed in as `supply_expression`.
+    Custom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DemandModel {
+    // Uses `demand_slope` and `demand_intercept`.
+    Linear,
+    // price = a * quantity^(-1 / epsilon)
+    ConstantElasticity { a: f64, epsilon: f64 },
+    // Typed in as `demand_expression`.
+    Custom,
+    // A second group of buyers with straight demand of its own, added
+    // sideways to the first, which uses `demand_slope` and `demand_intercept`.
+    TwoGroups { slope: f64, intercept: f64 },
+}
+
+// How the shift sliders move a curve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ShiftMode {
+    // Up or down by the shift at every quantity: a change in how much is
+    // wanted or offered at every price.
+    #[default]
+    Parallel,
+    // Steeper or flatter about where the curve meets the price axis: a change
+    // in responsiveness. Every `ROTATION_DOUBLING` of shift doubles the slope
+    // of supply or halves that of demand.
+    Rotate,
+}
+
+pub const ROTATION_DOUBLING: f64 = 5.0;
+
+// A price for every quantity; what supply and demand curves of any shape
+// have in common.
+pub trait Curve {
+    fn price(&self, quantity: f64) -> f64;
+}
+
+pub struct LinearCurve {
+    pub slope: f64,
+    pub intercept: f64,
+}
+
+impl Curve for LinearCurve {
+    fn price(&self, quantity: f64) -> f64 {
+        self.slope * quantity + self.intercept
+    }
+}
+
+pub struct QuadraticCurve {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+}
+
+impl Curve for QuadraticCurve {
+    fn price(&self, quantity: f64) -> f64 {
+        self.a * quantity * quantity + self.b * quantity + self.c
+    }
+}
+
+pub struct ConstantElasticityCurve {
+    pub a: f64,
+    pub epsilon: f64,
+}
+
+impl Curve for ConstantElasticityCurve {
+    fn price(&self, quantity: f64) -> f64 {
+        (self.a * quantity.max(MIN_QUANTITY).powf(-1.0 / self.epsilon)).min(MAX_WILLINGNESS_TO_PAY)
+    }
+}
+
+// Groups of buyers with straight demand curves, added up sideways: at every
+// price the market wants what all the groups want together.
+pub struct AggregateDemand {
+    pub groups: [LinearCurve; 2],
+}
+
+impl AggregateDemand {
+    // What the groups want at `price` between them, each nothing above the
+    // most it would pay.
+    fn quantity(&self, price: f64) -> f64 {
+        self.groups.iter().map(|group| ((price - group.intercept) / group.slope).max(0.0)).sum()
+    }
+}
+
+impl Curve for AggregateDemand {
+    fn price(&self, quantity: f64) -> f64 {
+        let [first, second] = &self.groups;
+        let (high, low) = if first.intercept >= second.intercept { (first, second) } else { (second, first) };
+        // Only the group that pays more buys until the price falls to what
+        // the other would pay, where the curve kinks and flattens.
+        let joined = (low.intercept - high.intercept) / high.slope;
+        if quantity <= joined {
+            high.price(quantity)
+        } else {
+            (quantity + high.intercept / high.slope + low.intercept / low.slope)
+                / (high.slope.recip() + low.slope.recip())
+        }
+    }
+}
+
+impl Curve for Formula {
+    fn price(&self, quantity: f64) -> f64 {
+        self.eval(quantity)
+    }
+}
+
+// What a typed formula that does not parse prices anything at, leaving the
+// market invalid rather than quietly some other curve.
+struct Unparsed;
+
+impl Curve for Unparsed {
+    fn price(&self, _quantity: f64) -> f64 {
+        f64::NAN
+    }
+}
+
+fn parse_curve(expression: &str) -> Box<dyn Curve> {
+    match expression.parse::<Formula>() {
+        Ok(formula) => Box::new(formula),
+        Err(_) => Box::new(Unparsed),
+    }
+}
+
+// What a side of the market trades at some price: never less than nothing,
+// and never the far-off quantities a nearly flat curve would otherwise reach.
+fn clamp_quantity(quantity: f64) -> f64 {
+    quantity.clamp(0.0, MAX_QUANTITY)
+}
+
+// The quantity within the range at which a rising or falling `curve` reaches
+// `price`, by bisection. Demand that stays above the price wants without
+// limit, as constant-elasticity demand does at a price of zero.
+fn invert(curve: &dyn Curve, price: f64, rising: bool) -> f64 {
+    let short = |quantity: f64| (curve.price(quantity) < price) == rising;
+    if !short(0.0) {
+        return 0.0;
+    }
+    if short(MAX_QUANTITY) {
+        return if rising { MAX_QUANTITY } else { f64::INFINITY };
+    }
+    let (mut low, mut high) = (0.0, MAX_QUANTITY);
+    for _ in 0..100 {
+        let mid = 0.5 * (low + high);
+        if short(mid) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    0.5 * (low + high)
+}
+
+// Whether `curve` is finite across the range and never turns, rising for
+// supply and falling for demand.
+fn monotone(curve: &dyn Curve, rising: bool) -> bool {
+    let prices: Vec<f64> =
+        (0..=DEFAULT_SAMPLES).map(|i| curve.price(i as f64 * MAX_QUANTITY / DEFAULT_SAMPLES as f64)).collect();
+    prices.iter().all(|price| price.is_finite())
+        && prices.windows(2).all(|pair| if rising { pair[1] >= pair[0] } else { pair[1] <= pair[0] })
+}
+
+// A curve moved up or down by `offset` after stretching its quantities by
+// `stretch`, and never below a price of zero.
+struct ShiftedCurve {
+    curve: Box<dyn Curve>,
+    offset: f64,
+    stretch: f64,
+}
+
+impl Curve for ShiftedCurve {
+    fn price(&self, quantity: f64) -> f64 {
+        (self.curve.price(quantity * self.stretch) + self.offset).max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SurplusResult {
+    pub consumer_surplus: f64,
+    pub producer_surplus: f64,
+    pub quantity: f64,
+    // What buyers pay; differs from `seller_price` by any tax or subsidy.
+    pub price: f64,
+    pub seller_price: f64,
+    pub deadweight_loss: f64,
+    // Consumer surplus queued away under a shortage; already taken out of
+    // `consumer_surplus` and counted in `deadweight_loss`.
+    pub waiting_cost: f64,
+    pub tax_revenue: f64,
+    pub subsidy_cost: f64,
+    pub equilibrium_quantity: f64,
+    pub equilibrium_price: f64,
+    // Whether the price control or quota moves the market off the outcome it
+    // would reach without one.
+    pub binding: bool,
+}
+
+impl SurplusResult {
+    // Welfare counting government revenue as a gain and spending as a loss.
+    pub fn total_surplus(&self) -> f64 {
+        self.consumer_surplus + self.producer_surplus + self.tax_revenue - self.subsidy_cost
+    }
+
+    // How the gap between buyer and seller price splits into the rise in what
+    // buyers pay and the fall in what sellers receive, per unit, relative to
+    // the equilibrium price. `None` when there is no gap.
+    pub fn incidence(&self) -> Option<(f64, f64)> {
+        (self.price - self.seller_price > f64::EPSILON)
+            .then_some((self.price - self.equilibrium_price, self.equilibrium_price - self.seller_price))
+    }
+
+    // Surplus handed from producers to consumers by trading below the
+    // equilibrium price, or taken back, negative, by trading above it. Only a
+    // redistribution; what is lost outright is the deadweight loss.
+    pub fn transfer_to_consumers(&self) -> f64 {
+        (self.equilibrium_price - self.price) * self.quantity
+    }
+
+    // Social welfare valuing a unit of consumer surplus at `weight` and one of
+    // producer surplus at the rest, from 0 for sellers only to 1 for buyers
+    // only. An even weight of a half leaves pure efficiency.
+    pub fn weighted_welfare(&self, weight: f64) -> f64 {
+        weight * self.consumer_surplus + (1.0 - weight) * self.producer_surplus
+    }
+
+    // Whether nothing changes hands, as under a ceiling at or below the
+    // lowest cost, when the surplus areas shrink to lines.
+    pub fn no_trade(&self) -> bool {
+        self.quantity < 1e-9
+    }
+}
+
+// Traded quantity with the price buyers pay and the price sellers receive.
+struct Trade {
+    quantity: f64,
+    buyer_price: f64,
+    seller_price: f64,
+    binding: bool,
+}
+
+// Files saved by older versions lack newer fields, so anything missing falls
+// back to its default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PriceLimitSimulator {
+    pub price_limit: f64,
+    pub price_control: PriceControl,
+    pub quota: f64,
+    pub tax_per_unit: f64,
+    pub subsidy_per_unit: f64,
+    pub levy: Levy,
+    // Share of the consumer surplus that buyers spend on queueing and
+    // searching when a binding ceiling leaves them rationed.
+    pub waiting_cost_fraction: f64,
+    pub supply_model: SupplyModel,
+    pub supply_slope: f64,
+    pub supply_intercept: f64,
+    pub supply_shift: f64,
+    pub supply_expression: String,
+    pub demand_model: DemandModel,
+    pub demand_slope: f64,
+    pub demand_intercept: f64,
+    pub demand_shift: f64,
+    pub demand_expression: String,
+    pub shift_mode: ShiftMode,
+    // Trapezoids used wherever a surplus area has no closed form.
+    pub integration_steps: usize,
+}
+
+impl Default for PriceLimitSimulator {
+    fn default() -> Self {
+        Self {
+            price_limit: 10.0,
+            price_control: PriceControl::Ceiling,
+            quota: 10.0,
+            tax_per_unit: 0.0,
+            subsidy_per_unit: 0.0,
+            levy: Levy::Auto,
+            waiting_cost_fraction: 0.0,
+            supply_model: SupplyModel::Linear,
+            supply_slope: 0.5,
+            supply_intercept: 5.0,
+            supply_shift: 0.0,
+            supply_expression: "0.5q + 5".to_owned(),
+            demand_model: DemandModel::Linear,
+            demand_slope: -0.5,
+            demand_intercept: 15.0,
+            demand_shift: 0.0,
+            demand_expression: "15 - 0.5q".to_owned(),
+            shift_mode: ShiftMode::Parallel,
+            integration_steps: 200,
+        }
+    }
+}
+
+impl PriceLimitSimulator {
+    // The supply curve with its shift. The models stay plain data so markets
+    // can be compared, copied and saved; the curve is built from them when
+    // needed.
+    pub fn supply_curve(&self) -> Box<dyn Curve> {
+        let (offset, stretch) = self.supply_transform();
+        Box::new(ShiftedCurve { curve: self.unshifted_supply(), offset, stretch })
+    }
+
+    pub fn demand_curve(&self) -> Box<dyn Curve> {
+        let (offset, stretch) = self.demand_transform();
+        Box::new(ShiftedCurve { curve: self.unshifted_demand(), offset, stretch })
+    }
+
+    fn unshifted_supply(&self) -> Box<dyn Curve> {
+        match self.supply_model {
+            SupplyModel::Linear => {
+                Box::new(LinearCurve { slope: self.supply_slope, intercept: self.supply_intercept })
+            }
+            SupplyModel::Quadratic { a, b, c } => Box::new(QuadraticCurve { a, b, c }),
+            SupplyModel::Custom => parse_curve(&self.supply_expression),
+        }
+    }
+
+    fn unshifted_demand(&self) -> Box<dyn Curve> {
+        match self.demand_model {
+            DemandModel::Linear => {
+                Box::new(LinearCurve { slope: self.demand_slope, intercept: self.demand_intercept })
+            }
+            DemandModel::ConstantElasticity { a, epsilon } => Box::new(ConstantElasticityCurve { a, epsilon }),
+            DemandModel::Custom => parse_curve(&self.demand_expression),
+            DemandModel::TwoGroups { slope, intercept } => Box::new(self.aggregate_demand(slope, intercept)),
+        }
+    }
+
+    fn aggregate_demand(&self, slope: f64, intercept: f64) -> AggregateDemand {
+        AggregateDemand {
+            groups: [
+                LinearCurve { slope: self.demand_slope, intercept: self.demand_intercept },
+                LinearCurve { slope, intercept },
+            ],
+        }
+    }
+
+    // What the supply shift does to the unshifted curve: the price added at
+    // every quantity, and the factor quantities are multiplied by first.
+    pub fn supply_transform(&self) -> (f64, f64) {
+        self.shift_transform(self.supply_shift)
+    }
+
+    // Likewise for demand, whose slope flattens as its shift rises, so that
+    // a positive shift raises prices on either curve.
+    pub fn demand_transform(&self) -> (f64, f64) {
+        let (offset, stretch) = self.shift_transform(self.demand_shift);
+        (offset, stretch.recip())
+    }
+
+    fn shift_transform(&self, shift: f64) -> (f64, f64) {
+        match self.shift_mode {
+            ShiftMode::Parallel => (shift, 1.0),
+            ShiftMode::Rotate => (0.0, 2f64.powf(shift / ROTATION_DOUBLING)),
+        }
+    }
+
+    pub fn supply_formula(&self, quantity: f64) -> f64 {
+        self.supply_curve().price(quantity)
+    }
+
+    // How much sellers offer at `price`; the inverse of `supply_formula`.
+    pub fn supply_quantity(&self, price: f64) -> f64 {
+        let (offset, stretch) = self.supply_transform();
+        let price = price - offset;
+        let quantity = match self.supply_model {
+            SupplyModel::Linear => (price - self.supply_intercept) / self.supply_slope,
+            SupplyModel::Quadratic { c, .. } if price <= c => 0.0,
+            SupplyModel::Quadratic { a, b, c } if a <= f64::EPSILON => (price - c) / b.max(f64::EPSILON),
+            SupplyModel::Quadratic { a, b, c } => (-b + (b * b + 4.0 * a * (price - c)).sqrt()) / (2.0 * a),
+            SupplyModel::Custom => invert(&*self.unshifted_supply(), price, true),
+        };
+        clamp_quantity(quantity / stretch)
+    }
+
+    pub fn demand_formula(&self, quantity: f64) -> f64 {
+        self.demand_curve().price(quantity)
+    }
+
+    // How much buyers want at `price`; the inverse of `demand_formula`.
+    pub fn demand_quantity(&self, price: f64) -> f64 {
+        let (offset, stretch) = self.demand_transform();
+        let price = price - offset;
+        let quantity = match self.demand_model {
+            DemandModel::Linear => (self.demand_intercept - price) / -self.demand_slope,
+            DemandModel::ConstantElasticity { .. } if price >= MAX_WILLINGNESS_TO_PAY => 0.0,
+            // At a price of zero there is no limit to what buyers want, short
+            // of the range the model considers.
+            DemandModel::ConstantElasticity { .. } if price <= 0.0 => f64::INFINITY,
+            DemandModel::ConstantElasticity { a, epsilon } => (price / a).powf(-epsilon),
+            DemandModel::Custom => invert(&*self.unshifted_demand(), price, false),
+            DemandModel::TwoGroups { slope, intercept } => self.aggregate_demand(slope, intercept).quantity(price),
+        };
+        clamp_quantity(quantity / stretch)
+    }
+
+    // The quantities up to `x_max` at which the curves are tabulated and
+    // exported, `samples` equal steps apart; always the same number of them,
+    // however far the view reaches.
+    pub fn sampled_quantities(&self, x_max: f64, samples: usize) -> impl Iterator<Item = f64> {
+        (0..=samples).map(move |i| i as f64 * x_max / samples as f64)
+    }
+
+    // Points for drawing the supply curve up to `x_max`, from `samples`
+    // segments if it is curved and denser where it bends.
+    pub fn supply_points(&self, x_max: f64, samples: usize) -> Vec<[f64; 2]> {
+        let segments = match self.supply_model {
+            SupplyModel::Linear => STRAIGHT_SEGMENTS,
+            SupplyModel::Quadratic { .. } | SupplyModel::Custom => samples,
+        };
+        let curve = self.supply_curve();
+        sample_adaptively(|quantity| curve.price(quantity), x_max, segments)
+    }
+
+    // Likewise for demand.
+    pub fn demand_points(&self, x_max: f64, samples: usize) -> Vec<[f64; 2]> {
+        let segments = match self.demand_model {
+            DemandModel::Linear => STRAIGHT_SEGMENTS,
+            DemandModel::ConstantElasticity { .. } | DemandModel::Custom | DemandModel::TwoGroups { .. } => {
+                samples
+            }
+        };
+        let curve = self.demand_curve();
+        sample_adaptively(|quantity| curve.price(quantity), x_max, segments)
+    }
+
+    // Points for drawing the demand of each group of buyers on its own, with
+    // the same shift as the market's, when there are two.
+    pub fn demand_group_points(&self, x_max: f64) -> Option<[Vec<[f64; 2]>; 2]> {
+        let DemandModel::TwoGroups { slope, intercept } = self.demand_model else {
+            return None;
+        };
+        let (offset, stretch) = self.demand_transform();
+        Some(self.aggregate_demand(slope, intercept).groups.map(|group| {
+            let curve = ShiftedCurve { curve: Box::new(group), offset, stretch };
+            sample_adaptively(|quantity| curve.price(quantity), x_max, STRAIGHT_SEGMENTS)
+        }))
+    }
+
+    // Quantity at which buyers pay `wedge` more per unit than sellers receive,
+    // found by bisection so any pair of curves works. Where the curves cross
+    // more than once the first crossing is used, as that is where trade stops
+    // being worthwhile; without a crossing the quantity is clamped to the
+    // nearest end of the range.
+    fn wedge_quantity(&self, wedge: f64) -> f64 {
+        let (supply, demand) = (self.supply_curve(), self.demand_curve());
+        let gap = |q: f64| demand.price(q) - supply.price(q) - wedge;
+        if gap(0.0) <= 0.0 {
+            return 0.0;
+        }
+        // Scan for the first sign change so bisection brackets a single root.
+        let step = MAX_QUANTITY / SCAN_STEPS as f64;
+        let Some(mut high) = (1..=SCAN_STEPS).map(|i| i as f64 * step).find(|&q| gap(q) <= 0.0) else {
+            return MAX_QUANTITY;
+        };
+        let mut low = high - step;
+        for _ in 0..100 {
+            let mid = 0.5 * (low + high);
+            if gap(mid) > 0.0 {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        0.5 * (low + high)
+    }
+
+    // Where the curves cross at a positive quantity within the range, if they
+    // do at all. Both are clamped at a price of zero, so they may meet at the
+    // kink where one of them drops to zero and runs along the other, which is
+    // an equilibrium at a price of zero; where the gap between them only
+    // changes sign by jumping, as at a pole of a typed curve, they never meet.
+    pub fn find_equilibrium(&self) -> Option<(f64, f64)> {
+        let equilibrium_quantity = self.wedge_quantity(0.0);
+        let (supply_price, demand_price) =
+            (self.supply_formula(equilibrium_quantity), self.demand_formula(equilibrium_quantity));
+        let meet = (demand_price - supply_price).abs() <= MEETING_TOLERANCE * (1.0 + supply_price.abs());
+        (equilibrium_quantity > 0.0 && equilibrium_quantity < MAX_QUANTITY && meet)
+            .then_some((equilibrium_quantity, supply_price))
+    }
+
+    // Whether the parameters describe a market that can be analysed: every
+    // one finite, no flat or vertical straight curve to divide by, a crossing
+    // at a positive quantity within the range, and neither curve nearly
+    // vertical there.
+    pub fn is_valid(&self) -> bool {
+        let finite = [
+            self.price_limit,
+            self.quota,
+            self.tax_per_unit,
+            self.subsidy_per_unit,
+            self.waiting_cost_fraction,
+            self.supply_slope,
+            self.supply_intercept,
+            self.supply_shift,
+            self.demand_slope,
+            self.demand_intercept,
+            self.demand_shift,
+        ]
+        .iter()
+        .all(|value| value.is_finite());
+        let supply_shaped = match self.supply_model {
+            SupplyModel::Linear => self.supply_slope > 0.0,
+            SupplyModel::Quadratic { a, b, c } => a >= 0.0 && b >= 0.0 && a + b > 0.0 && c.is_finite(),
+            SupplyModel::Custom => monotone(&*self.unshifted_supply(), true),
+        };
+        let demand_shaped = match self.demand_model {
+            DemandModel::Linear => self.demand_slope < 0.0,
+            DemandModel::ConstantElasticity { a, epsilon } => a > 0.0 && epsilon > 0.0,
+            DemandModel::Custom => monotone(&*self.unshifted_demand(), false),
+            DemandModel::TwoGroups { slope, intercept } => {
+                self.demand_slope < 0.0 && slope < 0.0 && intercept.is_finite()
+            }
+        };
+        finite && supply_shaped && demand_shaped && self.find_equilibrium().is_some() && !self.near_vertical()
+    }
+
+    // Whether either curve is steeper than `MAX_SLOPE` where they cross,
+    // which leaves the areas too thin to draw or read off.
+    pub fn near_vertical(&self) -> bool {
+        let (demand_slope, supply_slope) = self.slopes(self.wedge_quantity(0.0));
+        demand_slope.abs().max(supply_slope.abs()) > MAX_SLOPE
+    }
+
+    // Point price elasticities of demand and of supply at the equilibrium,
+    // (dQ/dP) * (P/Q) with dQ/dP the reciprocal of each curve's slope there.
+    // Demand elasticity comes out negative.
+    pub fn elasticities(&self) -> Option<(f64, f64)> {
+        let (quantity, price) = self.find_equilibrium()?;
+        let (demand_slope, supply_slope) = self.slopes(quantity);
+        Some((price / (demand_slope * quantity), price / (supply_slope * quantity)))
+    }
+
+    // How fast the demand and supply prices change with quantity at
+    // `quantity`, dP/dq for each curve. A central difference, narrow enough
+    // to stay on the positive side of any quantity above zero.
+    pub fn slopes(&self, quantity: f64) -> (f64, f64) {
+        let step = (1e-4 * quantity).max(1e-6);
+        let slope =
+            |curve: Box<dyn Curve>| (curve.price(quantity + step) - curve.price(quantity - step)) / (2.0 * step);
+        (slope(self.demand_curve()), slope(self.supply_curve()))
+    }
+
+    // The same market with every price control, tax and subsidy removed.
+    pub fn without_intervention(&self) -> PriceLimitSimulator {
+        PriceLimitSimulator {
+            price_control: PriceControl::None,
+            tax_per_unit: 0.0,
+            subsidy_per_unit: 0.0,
+            levy: Levy::Auto,
+            ..self.clone()
+        }
+    }
+
+    // The per-unit intervention that applies, with `Levy::Auto` resolved.
+    pub fn active_levy(&self) -> Levy {
+        match self.levy {
+            Levy::Auto if self.tax_per_unit > 0.0 => Levy::Tax,
+            Levy::Auto if self.subsidy_per_unit > 0.0 => Levy::Subsidy,
+            Levy::Auto => Levy::None,
+            levy => levy,
+        }
+    }
+
+    // The tax and the subsidy per unit that apply, zero for the one not
+    // chosen whatever its amount.
+    pub fn tax(&self) -> f64 {
+        if self.active_levy() == Levy::Tax { self.tax_per_unit } else { 0.0 }
+    }
+
+    pub fn subsidy(&self) -> f64 {
+        if self.active_levy() == Levy::Subsidy { self.subsidy_per_unit } else { 0.0 }
+    }
+
+    // Drops both amounts along with the choice between them.
+    pub fn clear_levy(&mut self) {
+        self.tax_per_unit = 0.0;
+        self.subsidy_per_unit = 0.0;
+        self.levy = Levy::None;
+    }
+
+    // The price limit at which a ceiling starts to bind below, or a floor
+    // above: what buyers pay with the tax or subsidy but no control, which
+    // without either is the equilibrium price. `None` for an invalid market.
+    pub fn binding_threshold(&self) -> Option<f64> {
+        let uncontrolled = PriceLimitSimulator { price_control: PriceControl::None, ..self.clone() };
+        self.is_valid().then(|| uncontrolled.calculate_surplus().price)
+    }
+
+    // The quota below which a quota binds: the quantity traded with the tax
+    // or subsidy but no control, which without either is the equilibrium
+    // quantity. `None` for an invalid market.
+    pub fn quota_threshold(&self) -> Option<f64> {
+        let uncontrolled = PriceLimitSimulator { price_control: PriceControl::None, ..self.clone() };
+        self.is_valid().then(|| uncontrolled.calculate_surplus().quantity)
+    }
+
+    // Where the quota stands against `quota_threshold`, `None` for an invalid
+    // market. Within the rounding of the figures shown it counts as set at
+    // the threshold, where it has only just stopped binding.
+    pub fn quota_standing(&self) -> Option<QuotaStanding> {
+        let threshold = self.quota_threshold()?;
+        Some(if (self.quota - threshold).abs() < AT_THRESHOLD {
+            QuotaStanding::AtThreshold
+        } else if self.quota < threshold {
+            QuotaStanding::Binding
+        } else {
+            QuotaStanding::NotBinding
+        })
+    }
+
+    // Moves the price control or quota to where it no longer binds, leaving
+    // it selected: a ceiling up to the most any buyer would pay, a floor down
+    // to zero and a quota out to the largest quantity considered.
+    pub fn relax_control(&mut self) {
+        match self.price_control {
+            PriceControl::Ceiling => self.price_limit = self.demand_formula(0.0),
+            PriceControl::Floor => self.price_limit = 0.0,
+            PriceControl::Quota => self.quota = MAX_QUANTITY,
+            PriceControl::None => {}
+        }
+    }
+
+    // The market in sentences for whoever cannot see the plot: where the
+    // curves start and demand runs out, where they cross and what any
+    // control or tax does to that. The words are looked up by key with
+    // `word` and the numbers written by `money` and `quantity`, so the text
+    // can follow the chosen language, currency and unit.
+    pub fn describe(
+        &self,
+        word: impl Fn(&'static str) -> &'static str,
+        money: impl Fn(f64) -> String,
+        quantity: impl Fn(f64) -> String,
+    ) -> String {
+        let mut demand = format!("{} {}", word("demand_falls_from"), money(self.demand_formula(0.0)));
+        let choke = self.demand_quantity(0.0);
+        if choke < MAX_QUANTITY {
+            demand.push_str(&format!(" {} {} {} {}", word("to"), money(0.0), word("at"), quantity(choke)));
+        }
+        let supply = format!("{} {}", word("supply_rises_from"), money(self.supply_formula(0.0)));
+        let mut sentences = vec![demand, supply];
+        sentences.push(match self.find_equilibrium() {
+            Some((amount, price)) => format!("{}: {}, {}", word("equilibrium"), money(price), quantity(amount)),
+            None => word("no_equilibrium").to_owned(),
+        });
+        if self.tax() > 0.0 {
+            sentences.push(format!("{}: {}", word("tax"), money(self.tax())));
+        }
+        if self.subsidy() > 0.0 {
+            sentences.push(format!("{}: {}", word("subsidy"), money(self.subsidy())));
+        }
+        let result = self.calculate_surplus();
+        let limit = money(self.price_limit.max(0.0));
+        let control = match self.price_control {
+            PriceControl::Ceiling => Some(format!("{}: {}", word("ceiling"), limit)),
+            PriceControl::Floor => Some(format!("{}: {}", word("floor"), limit)),
+            PriceControl::Quota => Some(format!("{}: {}", word("quota"), quantity(self.quota.max(0.0)))),
+            PriceControl::None => None,
+        };
+        if let Some(control) = control {
+            let standing = word(if result.binding { "binding" } else { "not_binding" }).to_lowercase();
+            sentences.push(format!("{control} ({standing})"));
+        }
+        let gap = match self.price_control {
+            PriceControl::Ceiling if result.binding => {
+                Some(("shortage", self.demand_quantity(result.price) - result.quantity))
+            }
+            PriceControl::Floor if result.binding => {
+                Some(("excess_supply", self.supply_quantity(result.seller_price) - result.quantity))
+            }
+            _ => None,
+        };
+        if let Some((key, gap)) = gap {
+            sentences.push(format!("{}: {}", word(key), quantity(gap)));
+        }
+        if result.deadweight_loss > 1e-9 {
+            sentences.push(format!("{}: {}", word("deadweight_loss"), money(result.deadweight_loss)));
+        }
+        format!("{}.", sentences.join(". "))
+    }
+
+    // Writes the parameters and the resulting surplus to the debug log, so a
+    // session can be followed with `RUST_LOG=prijsplafond=debug`.
+    pub fn log_state(&self) {
+        if log::log_enabled!(log::Level::Debug) {
+            let result = self.calculate_surplus();
+            log::debug!(
+                "{:?} -> (consumer surplus, producer surplus, deadweight loss) = ({:.2}, {:.2}, {:.2})",
+                self,
+                result.consumer_surplus,
+                result.producer_surplus,
+                result.deadweight_loss
+            );
+        }
+    }
+
+    // How far a substitute's demand curve rises, `cross_price` per unit that
+    // the last unit traded here is worth to buyers above the free-market
+    // price. Under a binding ceiling that is the scarcity the low price
+    // hides, so rationed buyers turn to the substitute.
+    pub fn spillover(&self, cross_price: f64) -> f64 {
+        let result = self.calculate_surplus();
+        cross_price * (self.demand_formula(result.quantity) - result.equilibrium_price)
+    }
+
+    // Every parameter that differs in `to`, sorted by name.
+    pub fn parameter_changes(&self, to: &PriceLimitSimulator) -> Vec<ParameterChange> {
+        // Both serialize to objects, so nothing here can fail.
+        let (from, to) = (serde_json::to_value(self).unwrap(), serde_json::to_value(to).unwrap());
+        let mut changes = Vec::new();
+        diff_values(String::new(), &from, &to, &mut changes);
+        changes
+    }
+
+    // The outcomes of `draws` markets whose supply and demand shifts are drawn
+    // independently from normal distributions around the set ones, with
+    // standard deviation `std_dev`. Draws that leave the curves without a
+    // crossing are left out. The same seed gives the same draws.
+    pub fn monte_carlo(&self, std_dev: f64, draws: usize, seed: u64) -> Vec<SurplusResult> {
+        let mut rng = Rng::new(seed);
+        let mut market = self.clone();
+        (0..draws)
+            .filter_map(|_| {
+                market.supply_shift = self.supply_shift + std_dev * rng.normal();
+                market.demand_shift = self.demand_shift + std_dev * rng.normal();
+                market.is_valid().then(|| market.calculate_surplus())
+            })
+            .collect()
+    }
+
+    // The same market before any supply or demand shock.
+    pub fn without_shifts(&self) -> PriceLimitSimulator {
+        PriceLimitSimulator { supply_shift: 0.0, demand_shift: 0.0, ..self.clone() }
+    }
+
+    // The outcome at every price limit from 0 to 20 in steps of 0.1.
+    pub fn price_limit_sweep(&self) -> Vec<(f64, SurplusResult)> {
+        (0..=200)
+            .map(|i| {
+                let price_limit = i as f64 / 10.0;
+                (price_limit, PriceLimitSimulator { price_limit, ..self.clone() }.calculate_surplus())
+            })
+            .collect()
+    }
+
+    // The free market put under each intervention in turn, each set the share
+    // `severity` of the way from the equilibrium: a ceiling that share below
+    // the equilibrium price, a floor that share above it, a tax of that share
+    // of it, and a quota that share short of the equilibrium quantity.
+    pub fn matched_interventions(&self, severity: f64) -> Option<MatchedInterventions> {
+        let (quantity, price) = self.find_equilibrium()?;
+        let free = self.without_intervention();
+        let control = |price_control, price_limit, quota| {
+            PriceLimitSimulator { price_control, price_limit, quota, ..free.clone() }.calculate_surplus()
+        };
+        Some(MatchedInterventions {
+            ceiling: control(PriceControl::Ceiling, (1.0 - severity) * price, 0.0),
+            floor: control(PriceControl::Floor, (1.0 + severity) * price, 0.0),
+            tax: PriceLimitSimulator { tax_per_unit: severity * price, ..free.clone() }.calculate_surplus(),
+            quota: control(PriceControl::Quota, 0.0, (1.0 - severity) * quantity),
+            free_market: free.calculate_surplus(),
+        })
+    }
+
+    // The ceiling in the sweep that leaves buyers the most consumer surplus,
+    // with that surplus. Below it the shortage costs buyers more than the
+    // lower price gains them.
+    pub fn best_ceiling_for_consumers(&self) -> (f64, f64) {
+        self.best_ceiling_by(|result| result.consumer_surplus)
+    }
+
+    // The ceiling in the sweep with the most welfare weighted by `weight`, as
+    // in `SurplusResult::weighted_welfare`, with that welfare. Up to an even
+    // weight no binding ceiling beats leaving the price free.
+    pub fn best_ceiling_for_weight(&self, weight: f64) -> (f64, f64) {
+        self.best_ceiling_by(|result| result.weighted_welfare(weight))
+    }
+
+    fn best_ceiling_by(&self, measure: impl Fn(&SurplusResult) -> f64) -> (f64, f64) {
+        PriceLimitSimulator { price_control: PriceControl::Ceiling, ..self.clone() }
+            .price_limit_sweep()
+            .into_iter()
+            .map(|(price_limit, result)| (price_limit, measure(&result)))
+            .fold((0.0, f64::NEG_INFINITY), |best, candidate| if candidate.1 > best.1 { candidate } else { best })
+    }
+
+    // The outcome at every tax per unit from 0 to 20 in steps of 0.1.
+    pub fn tax_sweep(&self) -> Vec<(f64, SurplusResult)> {
+        (0..=200)
+            .map(|i| {
+                let tax_per_unit = i as f64 / 10.0;
+                let taxed = PriceLimitSimulator { tax_per_unit, levy: Levy::Tax, ..self.clone() };
+                (tax_per_unit, taxed.calculate_surplus())
+            })
+            .collect()
+    }
+
+    fn traded(&self) -> Trade {
+        let wedge = match self.active_levy() {
+            Levy::Tax => self.tax_per_unit,
+            Levy::Subsidy => -self.subsidy_per_unit,
+            Levy::Auto | Levy::None => 0.0,
+        };
+        // However generous a subsidy, buyers take no more than they want at a
+        // price of zero.
+        let mut actual_quantity = self.wedge_quantity(wedge).min(self.demand_quantity(0.0));
+        let mut buyer_price = self.demand_formula(actual_quantity);
+
+        // Price controls apply to what buyers pay; sellers receive that price
+        // minus the tax plus the subsidy. Limits below zero, which only the
+        // command line and scenario files can ask for, act as zero.
+        let (price_limit, quota) = (self.price_limit.max(0.0), self.quota.max(0.0));
+        let binding = match self.price_control {
+            // The ceiling is held against the tax-inclusive price, so a tax
+            // can make a ceiling bind that would not otherwise. Sellers then
+            // get the ceiling less the tax, and trade stops at whichever of
+            // the tax and the ceiling allows less.
+            PriceControl::Ceiling if price_limit < buyer_price => {
+                buyer_price = price_limit;
+                actual_quantity = actual_quantity.min(self.supply_quantity(price_limit - wedge));
+                true
+            }
+            PriceControl::Floor if price_limit > buyer_price => {
+                buyer_price = price_limit;
+                actual_quantity = self.demand_quantity(price_limit);
+                true
+            }
+            // Buyers bid the price up to what the last permitted unit is
+            // worth to them, and sellers pocket the difference.
+            PriceControl::Quota if quota < actual_quantity => {
+                actual_quantity = quota;
+                buyer_price = self.demand_formula(quota);
+                true
+            }
+            _ => false,
+        };
+        Trade {
+            quantity: actual_quantity,
+            buyer_price,
+            // Only where nothing is traded can the tax exceed what buyers pay.
+            seller_price: (buyer_price - wedge).max(0.0),
+            binding,
+        }
+    }
+
+    // Whether linear supply is still a straight line, rather than shifted so
+    // far down that it runs along a price of zero before rising, which the
+    // closed-form areas would take for a negative cost.
+    pub fn supply_is_straight(&self) -> bool {
+        self.supply_intercept + self.supply_transform().0 >= 0.0
+    }
+
+    // Consumer and producer surplus as the areas between each curve and the
+    // price its side of the market faces, integrated numerically so it holds
+    // for any curve shape.
+    pub fn integrate_surplus(&self) -> (f64, f64) {
+        let Trade {
+            quantity,
+            buyer_price,
+            seller_price,
+            ..
+        } = self.traded();
+        let steps = self.integration_steps;
+        let (supply, demand) = (self.supply_curve(), self.demand_curve());
+        (
+            integrate(|q| demand.price(q) - buyer_price, 0.0, quantity, steps),
+            integrate(|q| seller_price - supply.price(q), 0.0, quantity, steps),
+        )
+    }
+
+    // The trapezoids that `integrate_surplus` adds up, with `steps` under
+    // each area in place of `integration_steps`, as their (quantity, price)
+    // corners: those under demand for consumer surplus, then those over
+    // supply for producer surplus.
+    pub fn surplus_trapezoids(&self, steps: usize) -> (Vec<Trapezoid>, Vec<Trapezoid>) {
+        let Trade {
+            quantity,
+            buyer_price,
+            seller_price,
+            ..
+        } = self.traded();
+        let grid: Vec<f64> = trapezoid_grid(0.0, quantity, steps).collect();
+        let strips = |curve: Box<dyn Curve>, price: f64| {
+            grid.windows(2)
+                .map(|edge| {
+                    let (left, right) = (edge[0], edge[1]);
+                    [[left, price], [left, curve.price(left)], [right, curve.price(right)], [right, price]]
+                })
+                .collect()
+        };
+        (strips(self.demand_curve(), buyer_price), strips(self.supply_curve(), seller_price))
+    }
+
+    pub fn calculate_surplus(&self) -> SurplusResult {
+        // Without a crossing this is the nearest end of the range, which keeps
+        // the numbers finite while the UI reports the problem.
+        let equilibrium_quantity = self.wedge_quantity(0.0);
+        let equilibrium_price = self.supply_formula(equilibrium_quantity);
+        let Trade {
+            quantity: actual_quantity,
+            buyer_price,
+            seller_price,
+            binding,
+        } = self.traded();
+
+        // Off equilibrium one side of the market is rationed, so the surplus
+        // areas become trapezoids rather than triangles. Curved demand has no
+        // closed form and is integrated numerically.
+        let consumer_surplus = match self.demand_model {
+            DemandModel::Linear => {
+                0.5 * actual_quantity
+                    * ((self.demand_formula(0.0) - buyer_price).max(0.0)
+                        + (self.demand_formula(actual_quantity) - buyer_price).max(0.0))
+            }
+            DemandModel::ConstantElasticity { .. } | DemandModel::Custom | DemandModel::TwoGroups { .. } => {
+                self.integrate_surplus().0
+            }
+        };
+        let producer_surplus = match self.supply_model {
+            SupplyModel::Linear if self.supply_is_straight() => {
+                // Like consumer surplus, never negative where the cost of
+                // supplying a unit exceeds what sellers get for it.
+                0.5 * actual_quantity
+                    * ((seller_price - (self.supply_intercept + self.supply_transform().0)).max(0.0)
+                        + (seller_price - self.supply_formula(actual_quantity)).max(0.0))
+            }
+            // Shifted below a zero cost the curve runs along zero up to a
+            // kink and rises straight from there, so the area is the
+            // revenue less a triangle. Integrating instead would put the
+            // kink between steps and err by more than a slightly binding
+            // control changes.
+            SupplyModel::Linear if self.supply_slope > 0.0 => {
+                let (offset, stretch) = self.supply_transform();
+                let slope = self.supply_slope * stretch;
+                let kink = -(self.supply_intercept + offset) / slope;
+                let rising = (actual_quantity - kink).max(0.0);
+                (seller_price * actual_quantity - 0.5 * slope * rising * rising).max(0.0)
+            }
+            _ => self.integrate_surplus().1,
+        };
+        let tax_revenue = self.tax() * actual_quantity;
+        let subsidy_cost = self.subsidy() * actual_quantity;
+
+        // The trades that no longer happen between the controlled and the
+        // equilibrium quantity, or under a subsidy the units produced beyond
+        // it that cost more than buyers value them.
+        let deadweight_loss = match (self.demand_model, self.supply_model) {
+            (DemandModel::Linear, SupplyModel::Linear) if self.supply_is_straight() => {
+                0.5 * (equilibrium_quantity - actual_quantity)
+                    * (self.demand_formula(actual_quantity) - self.supply_formula(actual_quantity))
+            }
+            _ => {
+                let (supply, demand) = (self.supply_curve(), self.demand_curve());
+                integrate(
+                    |q| demand.price(q) - supply.price(q),
+                    actual_quantity,
+                    equilibrium_quantity,
+                    self.integration_steps,
+                )
+            }
+        };
+
+        // A shortage is rationed by something other than price, and what
+        // buyers lose to it benefits nobody.
+        let waiting_cost = if binding && self.price_control == PriceControl::Ceiling {
+            self.waiting_cost_fraction * consumer_surplus
+        } else {
+            0.0
+        };
+
+        SurplusResult {
+            consumer_surplus: consumer_surplus - waiting_cost,
+            producer_surplus,
+            quantity: actual_quantity,
+            price: buyer_price,
+            seller_price,
+            deadweight_loss: deadweight_loss + waiting_cost,
+            waiting_cost,
+            tax_revenue,
+            subsidy_cost,
+            equilibrium_quantity,
+            equilibrium_price,
+            binding,
+        }
+    }
+
+    // Where `result`, the outcome of this market, rests on numbers that are
+    // not what they seem: not finite at all, or cut off by one of the model's
+    // limits rather than worked out.
+    pub fn numeric_warnings(&self, result: &SurplusResult) -> Vec<NumericWarning> {
+        let mut warnings = Vec::new();
+        let figures = [
+            result.consumer_surplus,
+            result.producer_surplus,
+            result.quantity,
+            result.price,
+            result.seller_price,
+            result.deadweight_loss,
+            result.waiting_cost,
+            result.tax_revenue,
+            result.subsidy_cost,
+            result.equilibrium_quantity,
+            result.equilibrium_price,
+        ];
+        if figures.iter().any(|figure| !figure.is_finite()) {
+            warnings.push(NumericWarning::NotFinite);
+        }
+        let quantities = [
+            result.equilibrium_quantity,
+            self.demand_quantity(result.price),
+            self.supply_quantity(result.seller_price),
+        ];
+        if quantities.iter().any(|&quantity| quantity >= MAX_QUANTITY) {
+            warnings.push(NumericWarning::QuantityCapped);
+        }
+        let capped_demand = matches!(self.demand_model, DemandModel::ConstantElasticity { .. })
+            && self.unshifted_demand().price(0.0) >= MAX_WILLINGNESS_TO_PAY;
+        if capped_demand {
+            warnings.push(NumericWarning::WillingnessToPayCapped);
+        }
+        if self.price_limit < 0.0 || self.quota < 0.0 {
+            warnings.push(NumericWarning::NegativeLimit);
+        }
+        warnings
+    }
+
+    // How far the pieces of `result`, the outcome of this market, fall short
+    // of the whole area between demand and supply up to the equilibrium
+    // quantity, which they should add up to. Up to rounding this is zero for
+    // straight curves; anything more points at a mistake in one of them.
+    pub fn surplus_residual(&self, result: &SurplusResult) -> f64 {
+        let (supply, demand) = (self.supply_curve(), self.demand_curve());
+        let gains_from_trade = integrate(
+            |q| demand.price(q) - supply.price(q),
+            0.0,
+            result.equilibrium_quantity,
+            self.integration_steps,
+        );
+        gains_from_trade - result.total_surplus() - result.deadweight_loss
+    }
+
+    // The shaded regions for `result`, traced along the same curves, prices
+    // and quantities its numbers were computed from. The curves are held to
+    // the price lines where the surplus formulas clamp them.
+    pub fn surplus_outlines(&self, result: &SurplusResult) -> SurplusOutlines {
+        // egui fills polygons as a fan from the first vertex, which is only
+        // correct for curved demand when every edge is visible from it; a
+        // corner on the price line is.
+        let consumer_surplus = std::iter::once([0.0, result.price])
+            .chain(curve_path(0.0, result.quantity, |q| self.demand_formula(q).max(result.price)))
+            .chain(std::iter::once([result.quantity, result.price]))
+            .collect();
+        let producer_surplus = std::iter::once([0.0, result.seller_price])
+            .chain(curve_path(0.0, result.quantity, |q| self.supply_formula(q).min(result.seller_price)))
+            .chain(std::iter::once([result.quantity, result.seller_price]))
+            .collect();
+        let deadweight_loss = curve_path(result.quantity, result.equilibrium_quantity, |q| self.demand_formula(q))
+            .chain(curve_path(result.equilibrium_quantity, result.quantity, |q| self.supply_formula(q)))
+            .collect();
+        SurplusOutlines { consumer_surplus, producer_surplus, deadweight_loss }
+    }
+}
+
+// A parameter that differs between two markets, named by its path through the
+// saved form, such as `supply_model.Quadratic.a`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterChange {
+    pub name: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+// Descends into objects both sides have, so a change of one coefficient is
+// reported alone; anything else that differs is reported whole.
+fn diff_values(name: String, from: &Value, to: &Value, changes: &mut Vec<ParameterChange>) {
+    match (from, to) {
+        (Value::Object(from), Value::Object(to)) => {
+            for (key, value) in from {
+                let path = if name.is_empty() { key.clone() } else { format!("{name}.{key}") };
+                match to.get(key) {
+                    Some(other) => diff_values(path, value, other, changes),
+                    None => changes.push(ParameterChange {
+                        name: path,
+                        from: value.clone(),
+                        to: Value::Null,
+                    }),
+                }
+            }
+            for (key, value) in to.iter().filter(|(key, _)| !from.contains_key(*key)) {
+                let path = if name.is_empty() { key.clone() } else { format!("{name}.{key}") };
+                changes.push(ParameterChange { name: path, from: Value::Null, to: value.clone() });
+            }
+        }
+        _ if from != to => changes.push(ParameterChange { name, from: from.clone(), to: to.clone() }),
+        _ => {}
+    }
+}
+
+// Why the figures of a market deserve less trust than they seem to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumericWarning {
+    // Some figure came out infinite or not a number.
+    NotFinite,
+    // A quantity reached `MAX_QUANTITY`, where the model stops looking.
+    QuantityCapped,
+    // Constant-elasticity demand was cut off at `MAX_WILLINGNESS_TO_PAY`, and
+    // with it the consumer surplus.
+    WillingnessToPayCapped,
+    // A price limit or quota below zero, counted as zero.
+    NegativeLimit,
+}
+
+// A quota against the quantity the market would trade without it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaStanding {
+    Binding,
+    AtThreshold,
+    NotBinding,
+}
+
+// The outcomes of `matched_interventions`, and of leaving the market alone.
+pub struct MatchedInterventions {
+    pub ceiling: SurplusResult,
+    pub floor: SurplusResult,
+    pub tax: SurplusResult,
+    pub quota: SurplusResult,
+    pub free_market: SurplusResult,
+}
+
+impl MatchedInterventions {
+    // Every outcome by the name of its intervention, "no_intervention" for
+    // the free market, from the highest total surplus down.
+    pub fn ranked(&self) -> Vec<(&'static str, &SurplusResult)> {
+        let mut ranked = vec![
+            ("no_intervention", &self.free_market),
+            ("ceiling", &self.ceiling),
+            ("floor", &self.floor),
+            ("tax", &self.tax),
+            ("quota", &self.quota),
+        ];
+        ranked.sort_by(|(_, a), (_, b)| b.total_surplus().total_cmp(&a.total_surplus()));
+        ranked
+    }
+}
+
+// Outlines of the consumer surplus, producer surplus and deadweight loss, as
+// (quantity, price) vertices. The consumer surplus includes any share lost to
+// waiting, which the plot still shades as what buyers would have kept.
+pub struct SurplusOutlines {
+    pub consumer_surplus: Vec<[f64; 2]>,
+    pub producer_surplus: Vec<[f64; 2]>,
+    pub deadweight_loss: Vec<[f64; 2]>,
+}
+
+// The area enclosed by a simple polygon, by the shoelace formula.
+pub fn polygon_area(outline: &[[f64; 2]]) -> f64 {
+    let twice_area: f64 = outline
+        .iter()
+        .enumerate()
+        .map(|(i, [x0, y0])| {
+            let [x1, y1] = outline[(i + 1) % outline.len()];
+            x0 * y1 - x1 * y0
+        })
+        .sum();
+    0.5 * twice_area.abs()
+}
+
+// Points along a curve between two quantities, for outlining the regions it
+// bounds.
+fn curve_path(from: f64, to: f64, curve: impl Fn(f64) -> f64) -> impl Iterator<Item = [f64; 2]> {
+    const SEGMENTS: usize = 50;
+    (0..=SEGMENTS).map(move |i| {
+        let quantity = from + (to - from) * i as f64 / SEGMENTS as f64;
+        [quantity, curve(quantity)]
+    })
+}
+
+// A straight line, price = slope * quantity + intercept, fitted to observed
+// quantity and price pairs.
+#[derive(Debug, Clone, Copy)]
+pub struct LineFit {
+    pub slope: f64,
+    pub intercept: f64,
+    pub r_squared: f64,
+    // The typical distance, in price, of an observation from the line; zero
+    // when two points leave nothing to estimate it from.
+    pub standard_error: f64,
+}
+
+// Ordinary least squares; `None` unless at least two different quantities were
+// observed.
+pub fn fit_line(points: &[[f64; 2]]) -> Option<LineFit> {
+    let n = points.len() as f64;
+    let mean_quantity = points.iter().map(|[q, _]| q).sum::<f64>() / n;
+    let mean_price = points.iter().map(|[_, p]| p).sum::<f64>() / n;
+    let (mut covariance, mut variance) = (0.0, 0.0);
+    for [q, p] in points {
+        covariance += (q - mean_quantity) * (p - mean_price);
+        variance += (q - mean_quantity) * (q - mean_quantity);
+    }
+    if variance <= f64::EPSILON {
+        return None;
+    }
+    let slope = covariance / variance;
+    let intercept = mean_price - slope * mean_quantity;
+    let residual: f64 = points.iter().map(|[q, p]| (p - slope * q - intercept).powi(2)).sum();
+    let total: f64 = points.iter().map(|[_, p]| (p - mean_price).powi(2)).sum();
+    // Prices that never vary are matched exactly by the flat line.
+    let r_squared = if total > 0.0 { 1.0 - residual / total } else { 1.0 };
+    // Two degrees of freedom went into the slope and intercept.
+    let standard_error = if points.len() > 2 { (residual / (n - 2.0)).sqrt() } else { 0.0 };
+    Some(LineFit { slope, intercept, r_squared, standard_error })
+}
+
+// Samples `curve` from zero to `to`, splitting `segments` equal pieces further
+// wherever the curve's second difference shows it bending away from a straight
+// line.
+fn sample_adaptively(curve: impl Fn(f64) -> f64, to: f64, segments: usize) -> Vec<[f64; 2]> {
+    fn refine(curve: &impl Fn(f64) -> f64, from: [f64; 2], to: [f64; 2], depth: u32, points: &mut Vec<[f64; 2]>) {
+        let middle = 0.5 * (from[0] + to[0]);
+        let middle = [middle, curve(middle)];
+        if depth < MAX_REFINEMENTS && (from[1] + to[1] - 2.0 * middle[1]).abs() > 2.0 * SAMPLE_TOLERANCE {
+            refine(curve, from, middle, depth + 1, points);
+            refine(curve, middle, to, depth + 1, points);
+        } else {
+            points.push(to);
+        }
+    }
+    let mut points = vec![[0.0, curve(0.0)]];
+    for i in 1..=segments {
+        let quantity = to * i as f64 / segments as f64;
+        refine(&curve, *points.last().unwrap(), [quantity, curve(quantity)], 0, &mut points);
+    }
+    points
+}
+
+// A small seeded source of random numbers, SplitMix64, so that simulations
+// repeat exactly and need no operating system randomness.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    // Uniform on [0, 1), from the top 53 bits.
+    pub fn uniform(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        ((z ^ (z >> 31)) >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Standard normal, by the Box-Muller transform. One minus the uniform
+    // draw keeps the logarithm away from zero.
+    pub fn normal(&mut self) -> f64 {
+        let radius = (-2.0 * (1.0 - self.uniform()).ln()).sqrt();
+        radius * (2.0 * std::f64::consts::PI * self.uniform()).cos()
+    }
+}
+
+// `value` rounded to whole cents, halves away from zero. Products like
+// 1.005 * 100 land a hair below the half they stand for, so the cents are
+// first settled to a millionth; and a value that rounds to nothing comes out
+// as a plain zero, never the -0.00 `format!` makes of a tiny negative.
+pub fn round_to_cents(value: f64) -> f64 {
+    let cents = ((value * 100.0) * 1e6).round() / 1e6;
+    let cents = cents.round();
+    if cents == 0.0 {
+        0.0
+    } else {
+        cents / 100.0
+    }
+}
+
+// Four (quantity, price) corners: on the base line, up to the curve, along it
+// and back down, the two parallel sides standing on the quantity axis.
+pub type Trapezoid = [[f64; 2]; 4];
+
+// Its width times the mean length of its parallel sides.
+pub fn trapezoid_area([near_base, near_top, far_top, far_base]: &Trapezoid) -> f64 {
+    0.5 * (far_base[0] - near_base[0]) * ((near_top[1] - near_base[1]) + (far_top[1] - far_base[1])).abs()
+}
+
+// The `steps + 1` evenly spaced points from `from` to `to` on which the
+// trapezoids of `integrate` stand.
+fn trapezoid_grid(from: f64, to: f64, steps: usize) -> impl Iterator<Item = f64> {
+    let width = (to - from) / steps as f64;
+    (0..=steps).map(move |i| if i == steps { to } else { from + i as f64 * width })
+}
+
+// Trapezoidal approximation of the integral of `f` from `from` to `to`.
+fn integrate(f: impl Fn(f64) -> f64, from: f64, to: f64, steps: usize) -> f64 {
+    let width = (to - from) / steps as f64;
+    let interior: f64 = trapezoid_grid(from, to, steps).skip(1).take(steps.saturating_sub(1)).map(&f).sum();
+    width * (0.5 * (f(from) + f(to)) + interior)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "expected {expected}, got {actual}");
+    }
+
+    #[test]
+    fn non_binding_ceiling_keeps_equilibrium() {
+        let sim = PriceLimitSimulator {
+            price_limit: 12.0,
+            ..Default::default()
+        };
+        let result = sim.calculate_surplus();
+        assert_close(result.price, 10.0);
+        assert_close(result.quantity, 10.0);
+        assert!(!result.binding);
+        assert_close(result.consumer_surplus, 25.0);
+        assert_close(result.producer_surplus, 25.0);
+        assert_close(result.deadweight_loss, 0.0);
+    }
+
+    #[test]
+    fn binding_ceiling_reduces_quantity() {
+        let sim = PriceLimitSimulator {
+            price_limit: 8.0,
+            ..Default::default()
+        };
+        let result = sim.calculate_surplus();
+        assert!(result.binding);
+        assert_close(result.price, 8.0);
+        assert_close(result.quantity, 6.0);
+        assert_close(result.consumer_surplus, 33.0);
+        assert_close(result.producer_surplus, 9.0);
+        assert_close(result.deadweight_loss, 8.0);
+        // Of the free market's 25 each, consumers gain 12 from producers and
+        // both sides lose 4 outright.
+        assert_close(result.transfer_to_consumers(), 12.0);
+    }
+
+    #[test]
+    fn ceiling_below_the_lowest_cost_stops_all_trade() {
+        let result = PriceLimitSimulator { price_limit: 4.0, ..Default::default() }.calculate_surplus();
+        assert!(result.no_trade());
+        assert_close(result.consumer_surplus, 0.0);
+        assert_close(result.producer_surplus, 0.0);
+        assert_close(result.deadweight_loss, 50.0);
+        assert!(!PriceLimitSimulator { price_limit: 8.0, ..Default::default() }.calculate_surplus().no_trade());
+    }
+
+    #[test]
+    fn shaded_areas_match_the_reported_surplus() {
+        let markets = [
+            PriceLimitSimulator { price_limit: 8.0, ..Default::default() },
+            PriceLimitSimulator { price_limit: 12.0, price_control: PriceControl::Floor, ..Default::default() },
+            PriceLimitSimulator { price_limit: 20.0, tax_per_unit: 2.0, ..Default::default() },
+            PriceLimitSimulator { price_limit: 20.0, subsidy_per_unit: 2.0, ..Default::default() },
+            PriceLimitSimulator { price_limit: 8.0, waiting_cost_fraction: 0.5, ..Default::default() },
+        ];
+        for sim in markets {
+            let result = sim.calculate_surplus();
+            let outlines = sim.surplus_outlines(&result);
+            assert_close(polygon_area(&outlines.consumer_surplus), result.consumer_surplus + result.waiting_cost);
+            assert_close(polygon_area(&outlines.producer_surplus), result.producer_surplus);
+            assert_close(polygon_area(&outlines.deadweight_loss), result.deadweight_loss - result.waiting_cost);
+        }
+    }
+
+    #[test]
+    fn lists_the_parameters_that_changed() {
+        let before = PriceLimitSimulator::default();
+        assert!(before.parameter_changes(&before).is_empty());
+        let after = PriceLimitSimulator {
+            price_limit: 8.0,
+            price_control: PriceControl::Floor,
+            supply_model: SupplyModel::Quadratic { a: 0.05, b: 0.2, c: 5.0 },
+            ..Default::default()
+        };
+        let names: Vec<_> = before.parameter_changes(&after).into_iter().map(|change| change.name).collect();
+        assert_eq!(names, ["price_control", "price_limit", "supply_model"]);
+        let steeper = SupplyModel::Quadratic { a: 0.1, b: 0.2, c: 5.0 };
+        let changes = after.parameter_changes(&PriceLimitSimulator { supply_model: steeper, ..after.clone() });
+        assert_eq!(
+            changes,
+            [ParameterChange { name: "supply_model.Quadratic.a".into(), from: 0.05.into(), to: 0.1.into() }]
+        );
+    }
+
+    #[test]
+    fn rounds_to_cents_without_a_negative_zero() {
+        assert_eq!(format!("{:.2}", round_to_cents(-1e-12)), "0.00");
+        assert_eq!(format!("{:.2}", round_to_cents(-0.004)), "0.00");
+        assert_eq!(format!("{:.2}", round_to_cents(9.999999)), "10.00");
+        // Exact halves go up, including those floating point stores just
+        // below the half.
+        assert_eq!(round_to_cents(0.125), 0.13);
+        assert_eq!(round_to_cents(1.005), 1.01);
+        assert_eq!(round_to_cents(2.675), 2.68);
+        assert_eq!(round_to_cents(-0.125), -0.13);
+    }
+
+    #[test]
+    fn uncertain_shifts_spread_the_outcomes() {
+        let sim = PriceLimitSimulator::default();
+        // Without uncertainty every draw is the market itself.
+        let certain = sim.monte_carlo(0.0, 10, 1);
+        assert_eq!(certain.len(), 10);
+        assert!(certain.iter().all(|draw| (draw.equilibrium_price - 10.0).abs() < 1e-9));
+        let prices = |seed| -> Vec<f64> {
+            sim.monte_carlo(1.0, 2000, seed).iter().map(|draw| draw.equilibrium_price).collect()
+        };
+        assert_eq!(prices(1), prices(1));
+        let prices = prices(1);
+        // With the default slopes each shift moves the price by half its
+        // size, so the price spreads by sqrt(2) / 2 around 10.
+        let mean = prices.iter().sum::<f64>() / prices.len() as f64;
+        let spread = (prices.iter().map(|price| (price - mean).powi(2)).sum::<f64>() / prices.len() as f64).sqrt();
+        assert!((mean - 10.0).abs() < 0.1, "mean {mean}");
+        assert!((spread - 0.5f64.sqrt()).abs() < 0.05, "spread {spread}");
+    }
+
+    #[test]
+    fn relaxed_controls_no_longer_bind() {
+        for (price_control, price_limit, quota) in
+            [(PriceControl::Ceiling, 8.0, 0.0), (PriceControl::Floor, 12.0, 0.0), (PriceControl::Quota, 0.0, 4.0)]
+        {
+            let mut sim = PriceLimitSimulator { price_control, price_limit, quota, ..Default::default() };
+            assert!(sim.calculate_surplus().binding);
+            sim.relax_control();
+            assert_eq!(sim.price_control, price_control);
+            let result = sim.calculate_surplus();
+            assert!(!result.binding);
+            assert_close(result.quantity, 10.0);
+        }
+    }
+
+    #[test]
+    fn zero_ceiling_kills_the_market() {
+        let sim = PriceLimitSimulator {
+            price_limit: 0.0,
+            ..Default::default()
+        };
+        let result = sim.calculate_surplus();
+        assert_close(result.price, 0.0);
+        assert_close(result.quantity, 0.0);
+        assert_close(result.consumer_surplus, 0.0);
+        assert_close(result.producer_surplus, 0.0);
+        assert_close(result.deadweight_loss, 50.0);
+    }
+
+    #[test]
+    fn limits_below_zero_act_as_zero() {
+        for price_control in [PriceControl::Ceiling, PriceControl::Quota] {
+            let result =
+                PriceLimitSimulator { price_control, price_limit: -5.0, quota: -5.0, ..Default::default() }
+                    .calculate_surplus();
+            assert_close(result.quantity, 0.0);
+            assert_close(result.consumer_surplus, 0.0);
+            assert_close(result.producer_surplus, 0.0);
+        }
+        let taxed_out = PriceLimitSimulator { tax_per_unit: 20.0, ..Default::default() }.calculate_surplus();
+        assert_close(taxed_out.quantity, 0.0);
+        assert!(taxed_out.seller_price >= 0.0);
+    }
+
+    #[test]
+    fn supply_priced_out_of_the_market_trades_nothing() {
+        // Supply starting at 17 is above the 15 the keenest buyer would pay.
+        let controls = [PriceControl::None, PriceControl::Ceiling, PriceControl::Floor, PriceControl::Quota];
+        for price_control in controls {
+            let sim = PriceLimitSimulator { price_control, supply_shift: 12.0, ..Default::default() };
+            let result = sim.calculate_surplus();
+            assert_close(result.quantity, 0.0);
+            assert_close(result.producer_surplus, 0.0);
+            assert_close(result.consumer_surplus, 0.0);
+        }
+    }
+
+    #[test]
+    fn supply_shifted_below_zero_cost_earns_no_phantom_surplus() {
+        // Supply runs along a price of zero up to 30 units, where demand
+        // reaches zero too: buyers get the goods for free.
+        let sim = PriceLimitSimulator {
+            price_control: PriceControl::None,
+            supply_shift: -20.0,
+            ..Default::default()
+        };
+        let result = sim.calculate_surplus();
+        assert_close(result.quantity, 30.0);
+        assert_close(result.price, 0.0);
+        assert!(result.producer_surplus.abs() < 1e-6);
+        assert!((result.consumer_surplus - 225.0).abs() < 1e-3);
+
+        // Supply now runs along zero up to 6 units and meets demand at 9,
+        // for 1.5. A ceiling just under that costs exactly the small
+        // triangle up to the equilibrium: sellers offer 8.8 units for
+        // revenue of 12.32, less the 1.96 beneath the rising stretch.
+        let kinked = PriceLimitSimulator { supply_shift: -8.0, demand_shift: -9.0, ..sim.clone() };
+        let free_market = kinked.calculate_surplus();
+        let ceiling = PriceLimitSimulator { price_control: PriceControl::Ceiling, price_limit: 1.4, ..kinked };
+        let result = ceiling.calculate_surplus();
+        assert!(result.binding);
+        assert_close(result.producer_surplus, 10.36);
+        assert_close(result.deadweight_loss, 0.02);
+        assert_close(free_market.total_surplus() - result.total_surplus(), result.deadweight_loss);
+
+        let collapsed = PriceLimitSimulator { demand_shift: -20.0, ..sim }.calculate_surplus();
+        assert_close(collapsed.quantity, 0.0);
+        assert_close(collapsed.consumer_surplus, 0.0);
+        assert_close(collapsed.producer_surplus, 0.0);
+    }
+
+    #[test]
+    fn total_surplus_drop_equals_deadweight_loss() {
+        let free_market = PriceLimitSimulator::default()
+            .without_intervention()
+            .calculate_surplus()
+            .total_surplus();
+        for sim in [
+            PriceLimitSimulator {
+                price_limit: 7.0,
+                ..Default::default()
+            },
+            PriceLimitSimulator {
+                price_control: PriceControl::None,
+                tax_per_unit: 2.0,
+                ..Default::default()
+            },
+            PriceLimitSimulator {
+                price_control: PriceControl::None,
+                subsidy_per_unit: 2.0,
+                ..Default::default()
+            },
+        ] {
+            let result = sim.calculate_surplus();
+            assert_close(result.total_surplus() + result.deadweight_loss, free_market);
+        }
+    }
+
+    #[test]
+    fn surplus_pieces_fill_the_area_between_the_curves() {
+        for sim in [
+            PriceLimitSimulator::default(),
+            PriceLimitSimulator {
+                price_limit: 7.0,
+                ..Default::default()
+            },
+            PriceLimitSimulator {
+                price_limit: 7.0,
+                waiting_cost_fraction: 0.5,
+                ..Default::default()
+            },
+            PriceLimitSimulator {
+                price_control: PriceControl::Floor,
+                price_limit: 12.0,
+                ..Default::default()
+            },
+            PriceLimitSimulator {
+                price_control: PriceControl::Quota,
+                quota: 4.0,
+                ..Default::default()
+            },
+            PriceLimitSimulator {
+                price_control: PriceControl::None,
+                tax_per_unit: 3.0,
+                ..Default::default()
+            },
+            PriceLimitSimulator {
+                price_control: PriceControl::None,
+                subsidy_per_unit: 2.0,
+                ..Default::default()
+            },
+        ] {
+            assert_close(sim.surplus_residual(&sim.calculate_surplus()), 0.0);
+        }
+    }
+
+    #[test]
+    fn two_groups_of_buyers_add_up_sideways() {
+        let sim = PriceLimitSimulator {
+            price_control: PriceControl::None,
+            demand_model: DemandModel::TwoGroups { slope: -1.0, intercept: 12.0 },
+            ..Default::default()
+        };
+        // Above a price of 12 only the first group buys, 30 - 2p; below it
+        // the second joins with 12 - p.
+        assert_close(sim.demand_quantity(13.0), 4.0);
+        assert_close(sim.demand_quantity(10.4), 10.8);
+        assert_close(sim.demand_formula(4.0), 13.0);
+        assert_close(sim.demand_formula(10.8), 10.4);
+        assert!(sim.is_valid());
+        let (quantity, price) = sim.find_equilibrium().unwrap();
+        assert_close(quantity, 10.8);
+        assert_close(price, 10.4);
+        // 18.6 from the first group alone and 3.84 past the kink, integrated
+        // across it.
+        let result = sim.calculate_surplus();
+        assert!((result.consumer_surplus - 22.44).abs() < 1e-3);
+        assert_close(result.producer_surplus, 29.16);
+        assert!(sim.demand_group_points(20.0).is_some());
+        assert!(PriceLimitSimulator::default().demand_group_points(20.0).is_none());
+    }
+
+    #[test]
+    fn constant_elasticity_demand_clears_against_supply() {
+        let sim = PriceLimitSimulator {
+            price_control: PriceControl::None,
+            demand_model: DemandModel::ConstantElasticity { a: 50.0, epsilon: 1.0 },
+            ..Default::default()
+        };
+        let result = sim.calculate_surplus();
+        // 50 / q = 0.5q + 5  =>  q = sqrt(125) - 5
+        assert!((result.quantity - (125.0f64.sqrt() - 5.0)).abs() < 1e-6);
+        assert!((sim.demand_formula(result.quantity) - result.price).abs() < 1e-6);
+        assert!(result.consumer_surplus.is_finite() && result.consumer_surplus > 0.0);
+        assert!((sim.demand_quantity(result.price) - result.quantity).abs() < 1e-6);
+    }
+
+    #[test]
+    fn integrated_surplus_matches_triangles_for_linear_curves() {
+        for sim in [
+            PriceLimitSimulator::default(),
+            PriceLimitSimulator {
+                price_limit: 7.0,
+                ..Default::default()
+            },
+            PriceLimitSimulator {
+                price_control: PriceControl::Floor,
+                price_limit: 12.0,
+                ..Default::default()
+            },
+            PriceLimitSimulator {
+                price_control: PriceControl::None,
+                tax_per_unit: 3.0,
+                ..Default::default()
+            },
+        ] {
+            let result = sim.calculate_surplus();
+            let (consumer_surplus, producer_surplus) = sim.integrate_surplus();
+            assert!((consumer_surplus - result.consumer_surplus).abs() < 1e-6);
+            assert!((producer_surplus - result.producer_surplus).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn equilibrium_requires_a_crossing() {
+        assert!(PriceLimitSimulator::default().find_equilibrium().is_some());
+
+        let sim = PriceLimitSimulator {
+            supply_intercept: 20.0,
+            ..Default::default()
+        };
+        assert!(sim.find_equilibrium().is_none());
+        assert_close(sim.calculate_surplus().equilibrium_quantity, 0.0);
+
+        let sim = PriceLimitSimulator {
+            demand_slope: -0.1,
+            supply_slope: 0.1,
+            demand_intercept: 30.0,
+            ..Default::default()
+        };
+        assert!(sim.find_equilibrium().is_none());
+        assert_close(sim.calculate_surplus().equilibrium_quantity, MAX_QUANTITY);
+    }
+
+    #[test]
+    fn degenerate_markets_are_invalid() {
+        assert!(PriceLimitSimulator::default().is_valid());
+        // Parallel curves never cross.
+        assert!(!PriceLimitSimulator { supply_slope: 0.0, demand_slope: 0.0, ..Default::default() }.is_valid());
+        // Supply starting above the highest willingness to pay.
+        assert!(!PriceLimitSimulator { supply_shift: 10.0, demand_shift: -10.0, ..Default::default() }.is_valid());
+        assert!(!PriceLimitSimulator { demand_intercept: f64::NAN, ..Default::default() }.is_valid());
+    }
+
+    #[test]
+    fn extreme_slopes_stay_on_the_plot() {
+        // Nearly flat curves offer or ask for quantities far beyond the range.
+        for sim in [
+            PriceLimitSimulator { supply_slope: 1e-12, price_limit: 6.0, ..Default::default() },
+            PriceLimitSimulator {
+                demand_slope: -1e-12,
+                price_control: PriceControl::Floor,
+                price_limit: 6.0,
+                ..Default::default()
+            },
+        ] {
+            assert!(sim.supply_quantity(20.0) <= MAX_QUANTITY);
+            assert!(sim.demand_quantity(0.0) <= MAX_QUANTITY);
+            let result = sim.calculate_surplus();
+            assert!(result.quantity <= MAX_QUANTITY);
+            assert!(result.consumer_surplus.is_finite() && result.deadweight_loss.is_finite());
+        }
+        // Nearly vertical ones still cross, but too steeply to draw.
+        for sim in [
+            PriceLimitSimulator { supply_slope: 1e6, ..Default::default() },
+            PriceLimitSimulator { demand_slope: -1e6, ..Default::default() },
+        ] {
+            assert!(sim.find_equilibrium().is_some());
+            assert!(sim.near_vertical());
+            assert!(!sim.is_valid());
+            let result = sim.calculate_surplus();
+            assert!(result.quantity.is_finite() && result.quantity < 1e-3);
+            assert!(result.consumer_surplus.is_finite() && result.producer_surplus.is_finite());
+        }
+        let steepest = PriceLimitSimulator { supply_slope: 5.0, demand_slope: -5.0, ..Default::default() };
+        assert!(!steepest.near_vertical());
+    }
+
+    #[test]
+    fn quadratic_supply_without_curvature_matches_linear() {
+        let linear = PriceLimitSimulator {
+            price_limit: 8.0,
+            tax_per_unit: 1.0,
+            ..Default::default()
+        };
+        let quadratic = PriceLimitSimulator {
+            supply_model: SupplyModel::Quadratic { a: 0.0, b: 0.5, c: 5.0 },
+            ..linear.clone()
+        };
+        let (expected, actual) = (linear.calculate_surplus(), quadratic.calculate_surplus());
+        assert!((actual.quantity - expected.quantity).abs() < 1e-6);
+        assert!((actual.consumer_surplus - expected.consumer_surplus).abs() < 1e-6);
+        assert!((actual.producer_surplus - expected.producer_surplus).abs() < 1e-6);
+        assert!((actual.deadweight_loss - expected.deadweight_loss).abs() < 1e-6);
+    }
+
+    #[test]
+    fn quadratic_supply_quantity_inverts_supply() {
+        let sim = PriceLimitSimulator {
+            supply_model: SupplyModel::Quadratic { a: 0.05, b: 0.2, c: 2.0 },
+            supply_shift: 1.0,
+            ..Default::default()
+        };
+        for quantity in [0.5, 4.0, 12.0] {
+            assert_close(sim.supply_quantity(sim.supply_formula(quantity)), quantity);
+        }
+        assert_close(sim.supply_quantity(2.5), 0.0);
+    }
+
+    #[test]
+    fn quantities_invert_the_curves_and_never_go_negative() {
+        let markets = [
+            PriceLimitSimulator::default(),
+            PriceLimitSimulator { supply_shift: 2.0, demand_shift: -1.5, ..Default::default() },
+            PriceLimitSimulator {
+                demand_model: DemandModel::ConstantElasticity { a: 50.0, epsilon: 1.0 },
+                ..Default::default()
+            },
+        ];
+        for sim in markets {
+            for quantity in [0.5, 4.0, 9.0] {
+                let (supply_price, demand_price) = (sim.supply_formula(quantity), sim.demand_formula(quantity));
+                assert_close(sim.supply_formula(sim.supply_quantity(supply_price)), supply_price);
+                assert_close(sim.demand_formula(sim.demand_quantity(demand_price)), demand_price);
+            }
+            assert_close(sim.supply_quantity(sim.supply_formula(0.0) - 1.0), 0.0);
+        }
+        let linear = PriceLimitSimulator::default();
+        assert_close(linear.demand_quantity(linear.demand_formula(0.0) + 1.0), 0.0);
+        let flat = PriceLimitSimulator { supply_slope: 1e-12, ..Default::default() };
+        assert_close(flat.supply_quantity(10.0), MAX_QUANTITY);
+    }
+
+    #[test]
+    fn typed_formulas_match_the_curves_they_spell_out() {
+        let typed = PriceLimitSimulator {
+            supply_model: SupplyModel::Custom,
+            demand_model: DemandModel::Custom,
+            ..Default::default()
+        };
+        let linear = PriceLimitSimulator::default();
+        let (typed_result, linear_result) = (typed.calculate_surplus(), linear.calculate_surplus());
+        assert!(typed.is_valid());
+        assert_close(typed_result.quantity, linear_result.quantity);
+        assert_close(typed_result.consumer_surplus, linear_result.consumer_surplus);
+        assert_close(typed_result.producer_surplus, linear_result.producer_surplus);
+        assert_close(typed_result.deadweight_loss, linear_result.deadweight_loss);
+        for price in [6.0, 9.0, 12.0] {
+            assert_close(typed.supply_quantity(price), linear.supply_quantity(price));
+            assert_close(typed.demand_quantity(price), linear.demand_quantity(price));
+        }
+
+        let unreadable = PriceLimitSimulator { supply_expression: "0.5q +".to_owned(), ..typed.clone() };
+        assert!(!unreadable.is_valid());
+        let falling = PriceLimitSimulator { supply_expression: "10 - q".to_owned(), ..typed };
+        assert!(!falling.is_valid());
+    }
+
+    #[test]
+    fn rotating_changes_the_slopes_but_not_the_intercepts() {
+        let sim = PriceLimitSimulator {
+            shift_mode: ShiftMode::Rotate,
+            supply_shift: ROTATION_DOUBLING,
+            demand_shift: ROTATION_DOUBLING,
+            price_control: PriceControl::None,
+            ..Default::default()
+        };
+        assert_close(sim.supply_formula(0.0), 5.0);
+        assert_close(sim.demand_formula(0.0), 15.0);
+        assert_close(sim.supply_formula(4.0), 9.0);
+        assert_close(sim.demand_formula(4.0), 14.0);
+        for price in [6.0, 10.0, 14.0] {
+            assert_close(sim.supply_formula(sim.supply_quantity(price)), price);
+            assert_close(sim.demand_formula(sim.demand_quantity(price)), price);
+        }
+        // Supply at 5 + q meets demand at 15 - q/4 where q = 8.
+        let result = sim.calculate_surplus();
+        assert_close(result.quantity, 8.0);
+        assert_close(result.producer_surplus, 32.0);
+    }
+
+    #[test]
+    fn waiting_in_line_dissipates_consumer_surplus() {
+        let sim = PriceLimitSimulator {
+            price_limit: 8.0,
+            waiting_cost_fraction: 0.25,
+            ..Default::default()
+        };
+        let result = sim.calculate_surplus();
+        assert_close(result.waiting_cost, 8.25);
+        assert_close(result.consumer_surplus, 24.75);
+        assert_close(result.deadweight_loss, 16.25);
+        // Without a shortage there is no queue.
+        let result = PriceLimitSimulator { price_limit: 12.0, ..sim }.calculate_surplus();
+        assert_close(result.waiting_cost, 0.0);
+    }
+
+    #[test]
+    fn rationed_buyers_spill_into_a_substitute() {
+        // At a ceiling of 8 only 6 units trade, which buyers value at 12.
+        let sim = PriceLimitSimulator { price_limit: 8.0, ..Default::default() };
+        assert_close(sim.spillover(0.5), 1.0);
+        assert_close(sim.without_intervention().spillover(0.5), 0.0);
+    }
+
+    #[test]
+    fn binding_quota_raises_price() {
+        let sim = PriceLimitSimulator {
+            price_control: PriceControl::Quota,
+            quota: 6.0,
+            ..Default::default()
+        };
+        let result = sim.calculate_surplus();
+        assert_close(result.quantity, 6.0);
+        assert_close(result.price, 12.0);
+        assert_close(result.consumer_surplus, 9.0);
+        assert_close(result.producer_surplus, 33.0);
+        assert_close(result.deadweight_loss, 8.0);
+    }
+
+    #[test]
+    fn collapsed_demand_keeps_surplus_non_negative() {
+        // Demand now reaches zero at a quantity of 6, short of where the
+        // subsidised supply curve would cross it.
+        let sim = PriceLimitSimulator {
+            price_control: PriceControl::None,
+            demand_shift: -12.0,
+            subsidy_per_unit: 10.0,
+            ..Default::default()
+        };
+        let result = sim.calculate_surplus();
+        assert_close(result.quantity, 6.0);
+        assert_close(result.price, 0.0);
+        assert_close(result.consumer_surplus, 9.0);
+
+        for price_limit in [0.0, 2.0, 8.0] {
+            let sim = PriceLimitSimulator {
+                price_limit,
+                demand_shift: -14.0,
+                ..Default::default()
+            };
+            let result = sim.calculate_surplus();
+            assert!(result.consumer_surplus >= 0.0);
+            assert!(result.quantity <= sim.demand_quantity(0.0));
+        }
+    }
+
+    #[test]
+    fn elasticities_at_equilibrium() {
+        let (demand, supply) = PriceLimitSimulator::default().elasticities().unwrap();
+        assert!((demand + 2.0).abs() < 1e-6);
+        assert!((supply - 2.0).abs() < 1e-6);
+
+        let sim = PriceLimitSimulator {
+            demand_model: DemandModel::ConstantElasticity { a: 50.0, epsilon: 1.5 },
+            ..Default::default()
+        };
+        assert!((sim.elasticities().unwrap().0 + 1.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn adaptive_sampling_only_refines_curves() {
+        let straight = PriceLimitSimulator::default();
+        assert_eq!(straight.supply_points(20.0, 16).len(), STRAIGHT_SEGMENTS + 1);
+        let curved = PriceLimitSimulator {
+            demand_model: DemandModel::ConstantElasticity { a: 50.0, epsilon: 1.0 },
+            ..Default::default()
+        };
+        let points = curved.demand_points(20.0, 16);
+        assert!(points.len() > 16 + 1);
+        for pair in points.windows(2) {
+            let middle = 0.5 * (pair[0][0] + pair[1][0]);
+            let chord = 0.5 * (pair[0][1] + pair[1][1]);
+            assert!((curved.demand_formula(middle) - chord).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn slopes_follow_the_curve() {
+        let (demand_slope, supply_slope) = PriceLimitSimulator::default().slopes(3.0);
+        assert!((demand_slope + 0.5).abs() < 1e-6);
+        assert!((supply_slope - 0.5).abs() < 1e-6);
+        let curved = PriceLimitSimulator {
+            supply_model: SupplyModel::Quadratic { a: 0.05, b: 0.2, c: 5.0 },
+            ..Default::default()
+        };
+        // Marginal cost rises at 2aq + b.
+        assert!((curved.slopes(10.0).1 - 1.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn fits_a_line_to_observations() {
+        let fit = fit_line(&[[0.0, 15.0], [10.0, 10.0], [20.0, 5.0]]).unwrap();
+        assert_close(fit.slope, -0.5);
+        assert_close(fit.intercept, 15.0);
+        assert_close(fit.r_squared, 1.0);
+        assert_close(fit.standard_error, 0.0);
+
+        let fit = fit_line(&[[0.0, 0.0], [1.0, 2.0], [2.0, 1.0], [3.0, 3.0]]).unwrap();
+        assert_close(fit.slope, 0.8);
+        assert_close(fit.r_squared, 0.64);
+        assert_close(fit.standard_error, 0.9f64.sqrt());
+
+        assert!(fit_line(&[[5.0, 1.0], [5.0, 2.0]]).is_none());
+        assert!(fit_line(&[]).is_none());
+    }
+
+    #[test]
+    fn tax_and_ceiling_each_bind_on_their_own() {
+        // Neither: the ceiling sits above the equilibrium price of 10.
+        let neither = PriceLimitSimulator { price_limit: 12.0, ..Default::default() }.calculate_surplus();
+        assert!(!neither.binding);
+        assert_close(neither.quantity, 10.0);
+        // Only the tax: buyers pay 11, still under the ceiling.
+        let tax_only = PriceLimitSimulator { price_limit: 12.0, tax_per_unit: 2.0, ..Default::default() };
+        let result = tax_only.calculate_surplus();
+        assert!(!result.binding);
+        assert_close(result.quantity, 8.0);
+        assert_close(result.price, 11.0);
+        // Only the ceiling.
+        let ceiling_only = PriceLimitSimulator { price_limit: 8.0, ..Default::default() }.calculate_surplus();
+        assert!(ceiling_only.binding);
+        assert_close(ceiling_only.quantity, 6.0);
+        // A ceiling of 10.5 would not bind without the tax, but does with it.
+        let tipped = PriceLimitSimulator { price_limit: 10.5, ..tax_only }.calculate_surplus();
+        assert!(tipped.binding);
+        assert_close(tipped.quantity, 7.0);
+    }
+
+    #[test]
+    fn tax_and_ceiling_binding_together() {
+        // Buyers pay the ceiling of 9, sellers keep 7, and at 7 they offer
+        // only 4 units.
+        let sim = PriceLimitSimulator { price_limit: 9.0, tax_per_unit: 2.0, ..Default::default() };
+        let result = sim.calculate_surplus();
+        assert!(result.binding);
+        assert_close(result.quantity, 4.0);
+        assert_close(result.price, 9.0);
+        assert_close(result.seller_price, 7.0);
+        assert_close(result.consumer_surplus, 20.0);
+        assert_close(result.producer_surplus, 4.0);
+        assert_close(result.tax_revenue, 8.0);
+        assert_close(result.deadweight_loss, 18.0);
+        // Nothing is lost from the free-market total but the deadweight loss.
+        let free_market = sim.without_intervention().calculate_surplus();
+        assert_close(result.total_surplus() + result.deadweight_loss, free_market.total_surplus());
+    }
+
+    #[test]
+    fn warns_of_clamped_and_non_finite_figures() {
+        let warnings = |model: PriceLimitSimulator| model.numeric_warnings(&model.calculate_surplus());
+        assert_eq!(warnings(PriceLimitSimulator::default()), []);
+        // Buyers who would pay without limit for the first unit.
+        let unbounded = PriceLimitSimulator {
+            demand_model: DemandModel::Custom,
+            demand_expression: "10 / q".to_owned(),
+            ..Default::default()
+        };
+        assert!(warnings(unbounded).contains(&NumericWarning::NotFinite));
+        // At a zero ceiling flat demand wants more than the range holds.
+        let flat = PriceLimitSimulator { price_limit: 0.0, demand_slope: -0.1, ..Default::default() };
+        assert_eq!(warnings(flat), [NumericWarning::QuantityCapped]);
+        let elastic = PriceLimitSimulator {
+            demand_model: DemandModel::ConstantElasticity { a: 50.0, epsilon: 1.0 },
+            ..Default::default()
+        };
+        assert_eq!(warnings(elastic), [NumericWarning::WillingnessToPayCapped]);
+        let negative = PriceLimitSimulator { price_limit: -1.0, ..Default::default() };
+        assert_eq!(warnings(negative), [NumericWarning::NegativeLimit]);
+    }
+
+    #[test]
+    fn unbounded_curves_give_non_finite_samples_without_panicking() {
+        // Buyers who would pay without limit for the first unit, as a typed
+        // formula can have it: the first curve sample is infinite, which the
+        // plot drops, and the outcome is flagged rather than trusted.
+        let model = PriceLimitSimulator {
+            demand_model: DemandModel::Custom,
+            demand_expression: "10 / q".to_owned(),
+            ..Default::default()
+        };
+        let points = model.demand_points(50.0, DEFAULT_SAMPLES);
+        assert!(!points[0][1].is_finite());
+        assert!(points[1..].iter().all(|[quantity, price]| quantity.is_finite() && price.is_finite()));
+        let result = model.calculate_surplus();
+        assert!(!model.is_valid());
+        assert!(model.numeric_warnings(&result).contains(&NumericWarning::NotFinite));
+    }
+
+    #[test]
+    fn interventions_of_matched_severity_cut_trade_differently() {
+        // A fifth off or on the equilibrium price of 10, a tax of 2 and a
+        // quota of 8 against the equilibrium quantity of 10.
+        let matched = PriceLimitSimulator::default().matched_interventions(0.2).unwrap();
+        assert_close(matched.ceiling.quantity, 6.0);
+        assert_close(matched.ceiling.price, 8.0);
+        assert_close(matched.floor.quantity, 6.0);
+        assert_close(matched.floor.price, 12.0);
+        assert_close(matched.tax.quantity, 8.0);
+        assert_close(matched.tax.price, 11.0);
+        assert_close(matched.quota.quantity, 8.0);
+        assert_close(matched.quota.price, 11.0);
+    }
+
+    #[test]
+    fn trapezoids_add_up_to_the_integrated_surplus() {
+        let sim = PriceLimitSimulator {
+            supply_model: SupplyModel::Quadratic { a: 0.05, b: 0.2, c: 2.0 },
+            price_limit: 6.0,
+            ..Default::default()
+        };
+        let area = |trapezoids: &[Trapezoid]| -> f64 { trapezoids.iter().map(trapezoid_area).sum() };
+        for steps in [1, 4, 200] {
+            let (consumer, producer) = sim.surplus_trapezoids(steps);
+            assert_eq!((consumer.len(), producer.len()), (steps, steps));
+            let integrated = PriceLimitSimulator { integration_steps: steps, ..sim.clone() }.integrate_surplus();
+            assert!((area(&consumer) - integrated.0).abs() < 1e-9);
+            assert!((area(&producer) - integrated.1).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn equilibria_respect_the_clamp_at_zero() {
+        let free = PriceLimitSimulator { price_control: PriceControl::None, ..Default::default() };
+        // Supply runs along zero up to 4 and crosses demand above it, where
+        // the unclamped lines cross too.
+        let low_supply = PriceLimitSimulator { supply_shift: -7.0, ..free.clone() };
+        let (quantity, price) = low_supply.find_equilibrium().unwrap();
+        assert_close(quantity, 17.0);
+        assert_close(price, 6.5);
+        assert_close(low_supply.calculate_surplus().producer_surplus, 6.5 * 17.0 - 0.25 * 13.0 * 13.0);
+        // The unclamped lines would cross at 8 and a price of -1, but demand
+        // reaches zero at 6 while supply is still zero until 10: they meet at
+        // the kink and the good is free.
+        let both_low = PriceLimitSimulator { supply_shift: -10.0, demand_shift: -12.0, ..free.clone() };
+        let (quantity, price) = both_low.find_equilibrium().unwrap();
+        assert!((quantity - 6.0).abs() < 1e-9);
+        assert_close(price, 0.0);
+        let result = both_low.calculate_surplus();
+        assert_close(result.producer_surplus, 0.0);
+        assert!((result.consumer_surplus - 9.0).abs() < 1e-9);
+        // Demand that leaps past supply at a pole, from far above it to zero,
+        // without ever meeting it.
+        let pole = PriceLimitSimulator {
+            demand_model: DemandModel::Custom,
+            demand_expression: "10 + 1 / (12 - q)".to_owned(),
+            ..free
+        };
+        assert_eq!(pole.find_equilibrium(), None);
+        assert!(!pole.is_valid());
+    }
+
+    #[test]
+    fn controls_start_to_bind_at_the_threshold() {
+        assert_close(PriceLimitSimulator::default().binding_threshold().unwrap(), 10.0);
+        // A tax of 2 raises what buyers pay to 11, so a ceiling of 10.5 binds.
+        let taxed = PriceLimitSimulator { tax_per_unit: 2.0, ..Default::default() };
+        let threshold = taxed.binding_threshold().unwrap();
+        assert_close(threshold, 11.0);
+        for (price_control, below, above) in [
+            (PriceControl::Ceiling, true, false),
+            (PriceControl::Floor, false, true),
+        ] {
+            let at = |price_limit| PriceLimitSimulator { price_control, price_limit, ..taxed.clone() };
+            assert_eq!(at(threshold - 0.5).calculate_surplus().binding, below);
+            assert_eq!(at(threshold + 0.5).calculate_surplus().binding, above);
+        }
+        let flat = PriceLimitSimulator { supply_slope: 0.0, demand_slope: 0.0, ..Default::default() };
+        assert_eq!(flat.binding_threshold(), None);
+    }
+
+    #[test]
+    fn quotas_stop_binding_at_the_uncontrolled_quantity() {
+        let at = |quota, tax_per_unit| PriceLimitSimulator {
+            price_control: PriceControl::Quota,
+            quota,
+            tax_per_unit,
+            ..Default::default()
+        };
+        assert_close(at(0.0, 0.0).quota_threshold().unwrap(), 10.0);
+        for (quota, standing, binding) in [
+            (8.0, QuotaStanding::Binding, true),
+            (10.0, QuotaStanding::AtThreshold, false),
+            (12.0, QuotaStanding::NotBinding, false),
+        ] {
+            let sim = at(quota, 0.0);
+            assert_eq!(sim.quota_standing(), Some(standing));
+            assert_eq!(sim.calculate_surplus().binding, binding);
+        }
+        // A tax of 2 already cuts trade to 8, so a quota of 9 does nothing.
+        let taxed = at(9.0, 2.0);
+        assert_close(taxed.quota_threshold().unwrap(), 8.0);
+        assert_eq!(taxed.quota_standing(), Some(QuotaStanding::NotBinding));
+    }
+
+    #[test]
+    fn describes_the_market_in_words() {
+        // The same words as the English labels of the app.
+        let word = |key| match key {
+            "demand_falls_from" => "Demand falls from",
+            "supply_rises_from" => "Supply rises from",
+            "equilibrium" => "Equilibrium",
+            "no_equilibrium" => "No market equilibrium for these parameters",
+            "binding" => "Binding",
+            "not_binding" => "Not binding",
+            "shortage" => "Shortage",
+            "excess_supply" => "Excess Supply",
+            "deadweight_loss" => "Deadweight Loss",
+            key => key,
+        };
+        let describe = |model: PriceLimitSimulator| {
+            model.describe(word, |value| format!("€{value:.2}"), |value| format!("{value:.2} units"))
+        };
+        let at = |price_control, price_limit| PriceLimitSimulator {
+            price_control,
+            price_limit,
+            ..Default::default()
+        };
+        let curves = "Demand falls from €15.00 to €0.00 at 30.00 units. Supply rises from €5.00. \
+                      Equilibrium: €10.00, 10.00 units";
+        assert_eq!(
+            describe(at(PriceControl::Ceiling, 7.0)),
+            format!("{curves}. ceiling: €7.00 (binding). Shortage: 12.00 units. Deadweight Loss: €18.00.")
+        );
+        assert_eq!(
+            describe(at(PriceControl::Floor, 12.0)),
+            format!("{curves}. floor: €12.00 (binding). Excess Supply: 8.00 units. Deadweight Loss: €8.00.")
+        );
+        assert_eq!(
+            describe(at(PriceControl::Ceiling, 11.0)),
+            format!("{curves}. ceiling: €11.00 (not binding).")
+        );
+        let taxed = PriceLimitSimulator { tax_per_unit: 2.0, ..at(PriceControl::None, 0.0) };
+        assert_eq!(describe(taxed), format!("{curves}. tax: €2.00. Deadweight Loss: €2.00."));
+        let quota = PriceLimitSimulator { quota: 8.0, ..at(PriceControl::Quota, 0.0) };
+        assert_eq!(describe(quota), format!("{curves}. quota: 8.00 units (binding). Deadweight Loss: €2.00."));
+        let apart = PriceLimitSimulator { supply_shift: 20.0, ..at(PriceControl::None, 0.0) };
+        assert_eq!(
+            describe(apart),
+            "Demand falls from €15.00 to €0.00 at 30.00 units. Supply rises from €25.00. \
+             No market equilibrium for these parameters."
+        );
+    }
+
+    #[test]
+    fn only_the_chosen_levy_applies_and_the_other_waits() {
+        let free = PriceLimitSimulator { price_control: PriceControl::None, ..Default::default() };
+        let taxed = PriceLimitSimulator { tax_per_unit: 2.0, ..free.clone() }.calculate_surplus();
+        let subsidised = PriceLimitSimulator { subsidy_per_unit: 1.0, ..free.clone() }.calculate_surplus();
+        // Set up without a choice, as in older files, a tax wins.
+        let mut sim = PriceLimitSimulator { tax_per_unit: 2.0, subsidy_per_unit: 1.0, ..free.clone() };
+        assert_eq!(sim.active_levy(), Levy::Tax);
+        assert_eq!(sim.calculate_surplus(), taxed);
+        sim.levy = Levy::Subsidy;
+        assert_eq!((sim.tax(), sim.subsidy()), (0.0, 1.0));
+        assert_eq!(sim.calculate_surplus(), subsidised);
+        sim.levy = Levy::None;
+        assert_eq!(sim.calculate_surplus(), free.calculate_surplus());
+        sim.levy = Levy::Tax;
+        assert_eq!((sim.tax_per_unit, sim.subsidy_per_unit), (2.0, 1.0));
+        assert_eq!(sim.calculate_surplus(), taxed);
+        sim.clear_levy();
+        assert_eq!(sim.active_levy(), Levy::None);
+        assert_eq!((sim.tax_per_unit, sim.subsidy_per_unit), (0.0, 0.0));
+        sim.levy = Levy::Subsidy;
+        assert_eq!(sim.calculate_surplus(), free.calculate_surplus());
+    }
+
+    #[test]
+    fn leaving_the_market_alone_ranks_first() {
+        // Trading 8 of the 10 units loses 2, trading 6 loses 8, out of 50.
+        let matched = PriceLimitSimulator::default().matched_interventions(0.2).unwrap();
+        let ranked: Vec<(&str, f64)> =
+            matched.ranked().into_iter().map(|(name, outcome)| (name, outcome.total_surplus())).collect();
+        assert_eq!(ranked[0].0, "no_intervention");
+        for ((_, total_surplus), expected) in ranked.iter().zip([50.0, 48.0, 48.0, 42.0, 42.0]) {
+            assert_close(*total_surplus, expected);
+        }
+    }
+
+    #[test]
+    fn consumers_do_best_under_a_moderate_ceiling() {
+        // Consumer surplus under a ceiling p is (p - 5)(35 - 3p), greatest at
+        // p = 25/3, between the sweep's steps of 0.1.
+        let (price_limit, consumer_surplus) = PriceLimitSimulator::default().best_ceiling_for_consumers();
+        assert_close(price_limit, 8.3);
+        assert!((consumer_surplus - 100.0 / 3.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn the_weight_on_consumers_decides_whether_a_ceiling_helps() {
+        let sim = PriceLimitSimulator::default();
+        let (price_limit, welfare) = sim.best_ceiling_for_weight(1.0);
+        assert_eq!((price_limit, welfare), sim.best_ceiling_for_consumers());
+
+        let free_market = sim.without_intervention().calculate_surplus();
+        for weight in [0.0, 0.5] {
+            let (price_limit, welfare) = sim.best_ceiling_for_weight(weight);
+            assert!(price_limit >= free_market.equilibrium_price - 1e-9);
+            assert_close(welfare, free_market.weighted_welfare(weight));
+        }
+    }
+
+    #[test]
+    fn tax_revenue_peaks_halfway_to_the_choke_tax() {
+        let sim = PriceLimitSimulator { price_control: PriceControl::None, ..Default::default() };
+        let (tax, peak) = sim
+            .tax_sweep()
+            .into_iter()
+            .max_by(|(_, a), (_, b)| a.tax_revenue.total_cmp(&b.tax_revenue))
+            .unwrap();
+        assert_close(tax, 5.0);
+        assert_close(peak.tax_revenue, 25.0);
+    }
+
+    #[test]
+    fn tax_burden_falls_on_the_less_elastic_side() {
+        let sim = PriceLimitSimulator {
+            price_control: PriceControl::None,
+            supply_slope: 1.0,
+            tax_per_unit: 3.0,
+            ..Default::default()
+        };
+        let (buyers, sellers) = sim.calculate_surplus().incidence().unwrap();
+        assert_close(buyers, 1.0);
+        assert_close(sellers, 2.0);
+
+        assert!(PriceLimitSimulator::default().calculate_surplus().incidence().is_none());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        // Markets anywhere within the slider ranges, under a price ceiling.
+        fn ceiling_market() -> impl Strategy<Value = PriceLimitSimulator> {
+            (0.0..=20.0, -10.0..=10.0, -10.0..=10.0).prop_map(|(price_limit, supply_shift, demand_shift)| {
+                PriceLimitSimulator { price_limit, supply_shift, demand_shift, ..Default::default() }
+            })
+        }
+
+        proptest! {
+            #[test]
+            fn surpluses_are_never_negative(sim in ceiling_market()) {
+                let result = sim.calculate_surplus();
+                prop_assert!(result.consumer_surplus >= -1e-9, "consumer surplus {}", result.consumer_surplus);
+                prop_assert!(result.producer_surplus >= -1e-9, "producer surplus {}", result.producer_surplus);
+            }
+
+            #[test]
+            fn binding_ceiling_never_beats_the_free_market(sim in ceiling_market()) {
+                let result = sim.calculate_surplus();
+                prop_assume!(result.binding);
+                let free_market = sim.without_intervention().calculate_surplus();
+                prop_assert!(result.total_surplus() <= free_market.total_surplus() + 1e-9);
+            }
+
+            #[test]
+            fn lowering_a_binding_ceiling_never_helps(sim in ceiling_market(), cut in 0.0..=20.0f64) {
+                let lower = PriceLimitSimulator { price_limit: (sim.price_limit - cut).max(0.0), ..sim.clone() };
+                let (result, lower_result) = (sim.calculate_surplus(), lower.calculate_surplus());
+                prop_assume!(result.binding);
+                prop_assert!(lower_result.total_surplus() <= result.total_surplus() + 1e-9);
+            }
+        }
+    }
+}
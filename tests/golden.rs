@@ -0,0 +1,117 @@
+use prijsplafond::model::{DemandModel, PriceControl, PriceLimitSimulator, SupplyModel};
+use serde_json::{json, Value};
+
+// The committed outcomes, one case of the grid per line: its index, the
+// settings the grid varies and the outcome.
+const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/surplus.jsonl");
+// How far any reported figure may drift from the committed one.
+const TOLERANCE: f64 = 1e-9;
+
+// Every control on straight and curved markets, with and without a tax or
+// subsidy, and with each curve shifted either way.
+fn grid() -> Vec<PriceLimitSimulator> {
+    let controls = [
+        (PriceControl::None, 0.0, 0.0),
+        (PriceControl::Ceiling, 8.0, 0.0),
+        (PriceControl::Ceiling, 12.0, 0.0),
+        (PriceControl::Floor, 12.0, 0.0),
+        (PriceControl::Quota, 0.0, 4.0),
+    ];
+    let shapes = [
+        (SupplyModel::Linear, DemandModel::Linear),
+        (
+            SupplyModel::Quadratic { a: 0.05, b: 0.2, c: 5.0 },
+            DemandModel::ConstantElasticity { a: 50.0, epsilon: 1.0 },
+        ),
+    ];
+    let mut markets = Vec::new();
+    for (supply_model, demand_model) in shapes {
+        for (price_control, price_limit, quota) in controls {
+            for (tax_per_unit, subsidy_per_unit) in [(0.0, 0.0), (2.0, 0.0), (0.0, 2.0)] {
+                for supply_shift in [-4.0, 0.0, 4.0] {
+                    for demand_shift in [-4.0, 0.0, 4.0] {
+                        markets.push(PriceLimitSimulator {
+                            price_control,
+                            price_limit,
+                            quota,
+                            tax_per_unit,
+                            subsidy_per_unit,
+                            supply_model,
+                            demand_model,
+                            supply_shift,
+                            demand_shift,
+                            ..Default::default()
+                        });
+                    }
+                }
+            }
+        }
+    }
+    markets
+}
+
+// The settings the grid varies. Only these are kept beside each outcome,
+// so a new field on the market leaves the file alone.
+fn inputs(market: &PriceLimitSimulator) -> Value {
+    json!({
+        "price_control": market.price_control,
+        "price_limit": market.price_limit,
+        "quota": market.quota,
+        "tax_per_unit": market.tax_per_unit,
+        "subsidy_per_unit": market.subsidy_per_unit,
+        "supply_model": market.supply_model,
+        "demand_model": market.demand_model,
+        "supply_shift": market.supply_shift,
+        "demand_shift": market.demand_shift,
+    })
+}
+
+// Equal apart from numbers within the tolerance of each other.
+fn assert_matches(path: &str, actual: &Value, expected: &Value) {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => {
+            let keys = |object: &serde_json::Map<String, Value>| object.keys().cloned().collect::<Vec<_>>();
+            assert_eq!(keys(actual), keys(expected), "{path}: fields differ");
+            for (key, value) in actual {
+                assert_matches(&format!("{path}.{key}"), value, &expected[key]);
+            }
+        }
+        (Value::Number(actual), Value::Number(expected)) => {
+            let (actual, expected) = (actual.as_f64().unwrap(), expected.as_f64().unwrap());
+            assert!((actual - expected).abs() <= TOLERANCE, "{path}: expected {expected}, got {actual}");
+        }
+        _ => assert_eq!(actual, expected, "{path}"),
+    }
+}
+
+// Compares the grid's outcomes with the golden file. After an intended change
+// to the model, `UPDATE_GOLDEN=1 cargo test --test golden` rewrites the file,
+// whose diff then shows what moved.
+#[test]
+fn outcomes_match_the_golden_file() {
+    let lines: Vec<Value> = grid()
+        .iter()
+        .enumerate()
+        .map(|(case, market)| {
+            json!({ "case": case, "inputs": inputs(market), "outcome": market.calculate_surplus() })
+        })
+        .collect();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        let text: String = lines.iter().map(|line| format!("{line}\n")).collect();
+        std::fs::write(GOLDEN, text).unwrap();
+        return;
+    }
+    let golden = std::fs::read_to_string(GOLDEN).expect("missing golden file; run with UPDATE_GOLDEN=1");
+    let expected: std::collections::HashMap<u64, Value> = golden
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .map(|line| (line["case"].as_u64().expect("a line without its case"), line))
+        .collect();
+    assert_eq!(lines.len(), expected.len(), "the grid and the golden file differ in length");
+    for actual in &lines {
+        let case = actual["case"].as_u64().unwrap();
+        let expected = expected.get(&case).unwrap_or_else(|| panic!("case {case}: not in the golden file"));
+        assert_matches(&format!("case {case}"), &actual["inputs"], &expected["inputs"]);
+        assert_matches(&format!("case {case}"), &actual["outcome"], &expected["outcome"]);
+    }
+}